@@ -0,0 +1,37 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::hint::black_box;
+use std::time::Duration;
+
+// The plugin is only built as a cdylib, so the word game is pulled in directly.
+#[allow(dead_code, unused_imports)]
+#[path = "../src/wordle/mod.rs"]
+mod wordle;
+
+use wordle::{answers, best_guess, candidates, parse_feedback};
+
+fn solver(c: &mut Criterion) {
+    let history = parse_feedback(&["crane:bybbb"]).expect("feedback should parse");
+    let remaining = candidates(&history);
+
+    c.bench_function("candidates after one guess", |b| {
+        b.iter(|| candidates(black_box(&history)))
+    });
+
+    let mut group = c.benchmark_group("best_guess");
+    group
+        .sample_size(10)
+        .measurement_time(Duration::from_secs(10));
+    group.bench_function("every answer", |b| {
+        b.iter(|| best_guess(black_box(answers()), &[], false))
+    });
+    group.bench_function("after one guess", |b| {
+        b.iter(|| best_guess(black_box(&remaining), &history, false))
+    });
+    group.bench_function("after one guess in hard mode", |b| {
+        b.iter(|| best_guess(black_box(&remaining), &history, true))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, solver);
+criterion_main!(benches);
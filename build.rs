@@ -0,0 +1,20 @@
+use std::path::Path;
+use std::{env, fs};
+
+/// Compress the bundled word lists so they take up less space in the wasm module.
+fn main() {
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR should be set by cargo");
+
+    for list in ["answers", "allowed"] {
+        let source = format!("src/wordle/words/{}.txt", list);
+        println!("cargo:rerun-if-changed={}", source);
+
+        let words = fs::read(&source).expect("word list should be readable");
+        let compressed = miniz_oxide::deflate::compress_to_vec(&words, 10);
+        fs::write(
+            Path::new(&out_dir).join(format!("{}.txt.deflate", list)),
+            compressed,
+        )
+        .expect("compressed word list should be writable");
+    }
+}
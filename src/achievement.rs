@@ -0,0 +1,394 @@
+use crate::{announce, PuzzleRow};
+use anyhow::{anyhow, Result};
+use hank_pdk::{warn, Hank};
+use hank_types::database::PreparedStatement;
+use hank_types::message::Message;
+use hank_types::user::User;
+use serde::Deserialize;
+
+/// What has to happen to unlock an achievement, checked against a player's results in order.
+enum Rule {
+    /// Solve a puzzle in this many attempts.
+    SolvedIn(u32),
+    /// Play this many days in a row, optionally needing to solve, in fewer than some attempts or in
+    /// hard mode.
+    Streak {
+        days: usize,
+        solved: bool,
+        under: Option<u32>,
+        hard_mode: bool,
+    },
+    /// Solve the day after failing.
+    Comeback,
+    /// Play this many days in total.
+    Played(usize),
+}
+
+struct Achievement {
+    id: &'static str,
+    emoji: &'static str,
+    name: &'static str,
+    description: &'static str,
+    rule: Rule,
+}
+
+/// Every achievement that can be unlocked, add a new one by adding it here.
+const ACHIEVEMENTS: &[Achievement] = &[
+    Achievement {
+        id: "first_try",
+        emoji: "🎯",
+        name: "Hole in One",
+        description: "Solve a Wordle on the first try",
+        rule: Rule::SolvedIn(1),
+    },
+    Achievement {
+        id: "second_row",
+        emoji: "🟩",
+        name: "Green by Two",
+        description: "Get an all-green row on the 2nd guess",
+        rule: Rule::SolvedIn(2),
+    },
+    Achievement {
+        id: "under_four_streak",
+        emoji: "🔥",
+        name: "On Fire",
+        description: "Solve in under 4 for 10 days in a row",
+        rule: Rule::Streak {
+            days: 10,
+            solved: true,
+            under: Some(4),
+            hard_mode: false,
+        },
+    },
+    Achievement {
+        id: "hard_mode_month",
+        emoji: "💪",
+        name: "Hardcore",
+        description: "Solve on hard mode every day for a month",
+        rule: Rule::Streak {
+            days: 30,
+            solved: true,
+            under: None,
+            hard_mode: true,
+        },
+    },
+    Achievement {
+        id: "week_streak",
+        emoji: "📅",
+        name: "Regular",
+        description: "Play 7 days in a row",
+        rule: Rule::Streak {
+            days: 7,
+            solved: false,
+            under: None,
+            hard_mode: false,
+        },
+    },
+    Achievement {
+        id: "comeback",
+        emoji: "🔄",
+        name: "Comeback",
+        description: "Solve the day after an X",
+        rule: Rule::Comeback,
+    },
+    Achievement {
+        id: "played_100",
+        emoji: "💯",
+        name: "Centurion",
+        description: "Play 100 days",
+        rule: Rule::Played(100),
+    },
+    Achievement {
+        id: "played_365",
+        emoji: "🗓️",
+        name: "Year of Wordle",
+        description: "Play 365 days",
+        rule: Rule::Played(365),
+    },
+];
+
+impl Rule {
+    /// Whether the rule is met by the results, which are ordered by day.
+    fn is_met(&self, puzzles: &[PuzzleRow]) -> bool {
+        match *self {
+            Rule::SolvedIn(attempts) => puzzles
+                .iter()
+                .any(|row| row.puzzle.solved && row.puzzle.attempts == attempts),
+            Rule::Streak {
+                days,
+                solved,
+                under,
+                hard_mode,
+            } => {
+                let counts = |row: &PuzzleRow| {
+                    (!solved || row.puzzle.solved)
+                        && under.is_none_or(|under| row.puzzle.attempts < under)
+                        && (!hard_mode || row.puzzle.hard_mode)
+                };
+
+                let mut streak = 0;
+                let mut previous: Option<u32> = None;
+                for row in puzzles {
+                    let consecutive = previous.is_some_and(|day| day + 1 == row.puzzle.day_offset);
+                    streak = match (counts(row), consecutive) {
+                        (false, _) => 0,
+                        (true, true) => streak + 1,
+                        (true, false) => 1,
+                    };
+                    if streak >= days {
+                        return true;
+                    }
+                    previous = Some(row.puzzle.day_offset);
+                }
+
+                false
+            }
+            Rule::Comeback => puzzles.windows(2).any(|days| {
+                !days[0].puzzle.solved
+                    && days[1].puzzle.solved
+                    && days[0].puzzle.day_offset + 1 == days[1].puzzle.day_offset
+            }),
+            Rule::Played(days) => puzzles.len() >= days,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct UnlockedRow {
+    achievement_id: String,
+    unlocked_at: chrono::DateTime<chrono::Local>,
+}
+
+pub fn install() {
+    let query = "
+CREATE TABLE IF NOT EXISTS achievement (
+    user_id INTEGER NOT NULL,
+    achievement_id TEXT NOT NULL,
+    unlocked_at TEXT NOT NULL,
+    PRIMARY KEY(user_id, achievement_id)
+);
+";
+    let _ = Hank::db_query(PreparedStatement::new(query).build());
+}
+
+/// Check the user's results for newly unlocked achievements and announce them.
+pub fn evaluate(user: &User) {
+    let (puzzles, unlocked) = match (find_puzzles(user), find_unlocked(&user.id.to_string())) {
+        (Ok(puzzles), Ok(unlocked)) => (puzzles, unlocked),
+        (Err(e), _) | (_, Err(e)) => {
+            warn!("couldn't look up {}'s achievements: {}", user.name, e);
+            return;
+        }
+    };
+
+    for achievement in ACHIEVEMENTS {
+        if unlocked
+            .iter()
+            .any(|row| row.achievement_id == achievement.id)
+            || !achievement.rule.is_met(&puzzles)
+        {
+            continue;
+        }
+
+        if let Err(e) = insert_unlocked(user, achievement) {
+            warn!(
+                "couldn't unlock {} for {}: {}",
+                achievement.id, user.name, e
+            );
+            continue;
+        }
+
+        announce(format!(
+            "{} <@{}> unlocked **{}**: {}!",
+            achievement.emoji, user.id, achievement.name, achievement.description
+        ));
+    }
+}
+
+/// List the achievements a user has unlocked.
+pub fn badges(message: Message, user_id: Option<String>) {
+    let Some(user_id) = user_id.or_else(|| message.author.as_ref().map(|user| user.id.to_string()))
+    else {
+        return;
+    };
+
+    let unlocked = match find_unlocked(&user_id) {
+        Ok(unlocked) => unlocked,
+        Err(e) => {
+            warn!("couldn't look up achievements for {}: {}", user_id, e);
+            return;
+        }
+    };
+
+    let mut response = format!(
+        "**<@{}>'s badges** ({}/{})\n",
+        user_id,
+        unlocked.len(),
+        ACHIEVEMENTS.len()
+    );
+    for achievement in ACHIEVEMENTS {
+        match unlocked
+            .iter()
+            .find(|row| row.achievement_id == achievement.id)
+        {
+            Some(row) => response.push_str(&format!(
+                "{} **{}** - {} ({})\n",
+                achievement.emoji,
+                achievement.name,
+                achievement.description,
+                row.unlocked_at.date_naive()
+            )),
+            None => response.push_str(&format!(
+                "🔒 {} - {}\n",
+                achievement.name, achievement.description
+            )),
+        }
+    }
+
+    Hank::respond(response, message);
+}
+
+fn insert_unlocked(user: &User, achievement: &Achievement) -> Result<()> {
+    let statement = PreparedStatement::new(
+        "INSERT INTO achievement (user_id, achievement_id, unlocked_at) VALUES (?, ?, ?)",
+    )
+    .values([
+        user.id.to_string(),
+        achievement.id.to_string(),
+        Hank::datetime().to_rfc3339(),
+    ])
+    .build();
+
+    Hank::db_query(statement)
+        .map(|_| ())
+        .map_err(|e| anyhow!(e))
+}
+
+fn find_unlocked(user_id: &str) -> Result<Vec<UnlockedRow>> {
+    let statement = PreparedStatement::new(
+        "SELECT achievement_id, unlocked_at FROM achievement WHERE user_id = ?",
+    )
+    .values([user_id.to_string()])
+    .build();
+
+    Hank::db_fetch::<UnlockedRow>(statement).map_err(|e| anyhow!(e))
+}
+
+fn find_puzzles(user: &User) -> Result<Vec<PuzzleRow>> {
+    let statement =
+        PreparedStatement::new("SELECT * FROM puzzle WHERE submitted_by = ? ORDER BY day_offset")
+            .values([user.id.to_string()])
+            .build();
+
+    Hank::db_fetch::<PuzzleRow>(statement).map_err(|e| anyhow!(e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wordle::Puzzle;
+    use crate::wordle_launch_date;
+
+    /// A result for the day, with `X` for a fail and `*` on the end for hard mode.
+    fn row(day_offset: u32, result: &str) -> PuzzleRow {
+        let (attempts, hard_mode) = match result.strip_suffix('*') {
+            Some(attempts) => (attempts, "*"),
+            None => (result, ""),
+        };
+        let rows = match attempts {
+            "X" => vec!["⬛⬛⬛⬛⬛"; 6],
+            attempts => {
+                let mut rows = vec!["⬛🟨⬛⬛⬛"; attempts.parse::<usize>().unwrap() - 1];
+                rows.push("🟩🟩🟩🟩🟩");
+                rows
+            }
+        };
+        let puzzle = Puzzle::new(format!(
+            "Wordle {} {}/6{}\n\n{}",
+            day_offset,
+            attempts,
+            hard_mode,
+            rows.join("\n")
+        ))
+        .unwrap();
+
+        PuzzleRow {
+            id: day_offset.into(),
+            submitter: "player".into(),
+            submitted_by: 1,
+            submitted_at: chrono::Local::now(),
+            submitted_date: wordle_launch_date() + chrono::Duration::days(day_offset.into()),
+            puzzle,
+        }
+    }
+
+    fn rows(first_day: u32, results: &[&str]) -> Vec<PuzzleRow> {
+        (first_day..)
+            .zip(results)
+            .map(|(day_offset, result)| row(day_offset, result))
+            .collect()
+    }
+
+    fn streak(days: usize, solved: bool, under: Option<u32>, hard_mode: bool) -> Rule {
+        Rule::Streak {
+            days,
+            solved,
+            under,
+            hard_mode,
+        }
+    }
+
+    #[test]
+    fn resets_streaks() {
+        let rule = streak(3, false, Some(4), false);
+        assert!(rule.is_met(&rows(10, &["3", "2", "3"])));
+        assert!(!rule.is_met(&rows(10, &["3", "2", "4", "3", "1"])));
+        assert!(rule.is_met(&rows(10, &["3", "5", "3", "1", "2"])));
+        assert!(!rule.is_met(&rows(10, &["3", "X", "3"])));
+    }
+
+    #[test]
+    fn breaks_streaks_on_missed_days() {
+        let rule = streak(3, false, None, false);
+        let mut puzzles = rows(10, &["4", "5"]);
+        puzzles.extend(rows(13, &["3", "X"]));
+        assert!(!rule.is_met(&puzzles));
+
+        puzzles.push(row(15, "6"));
+        assert!(rule.is_met(&puzzles));
+    }
+
+    #[test]
+    fn counts_fails_only_when_solving_is_not_needed() {
+        let played = streak(3, false, None, false);
+        let solved = streak(3, true, None, false);
+        let puzzles = rows(10, &["X", "X", "X"]);
+
+        assert!(played.is_met(&puzzles));
+        assert!(!solved.is_met(&puzzles));
+    }
+
+    #[test]
+    fn needs_hard_mode_for_hard_mode_streaks() {
+        let rule = streak(3, true, None, true);
+        assert!(rule.is_met(&rows(10, &["4*", "6*", "2*"])));
+        assert!(!rule.is_met(&rows(10, &["4*", "6", "2*"])));
+        assert!(!rule.is_met(&rows(10, &["4*", "X*", "2*"])));
+    }
+
+    #[test]
+    fn counts_comebacks_on_consecutive_days() {
+        assert!(Rule::Comeback.is_met(&rows(10, &["3", "X", "4"])));
+        assert!(!Rule::Comeback.is_met(&rows(10, &["X", "X", "X"])));
+        assert!(!Rule::Comeback.is_met(&[row(10, "X"), row(12, "3")]));
+    }
+
+    #[test]
+    fn counts_solves_and_days_played() {
+        let puzzles = rows(10, &["X", "2", "5"]);
+        assert!(Rule::SolvedIn(2).is_met(&puzzles));
+        assert!(!Rule::SolvedIn(1).is_met(&puzzles));
+        assert!(Rule::Played(3).is_met(&puzzles));
+        assert!(!Rule::Played(4).is_met(&puzzles));
+    }
+}
@@ -0,0 +1,210 @@
+use crate::mode::Mode;
+use crate::{wordle_launch_date, PuzzleRow};
+use anyhow::{anyhow, Result};
+use chrono::{Datelike, Months, NaiveDate};
+use hank_pdk::{warn, Hank};
+use hank_types::database::PreparedStatement;
+use hank_types::message::Message;
+use std::collections::HashMap;
+
+const LEGEND: &str = "🟪 1 🟦 2 🟩 3 🟨 4 🟧 5 🟫 6 🟥 X ⬛ missed";
+
+/// How a player did on a day.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum DayResult {
+    Solved(u32),
+    Failed,
+    Missed,
+}
+
+impl From<&PuzzleRow> for DayResult {
+    fn from(row: &PuzzleRow) -> Self {
+        if row.puzzle.solved {
+            DayResult::Solved(row.puzzle.attempts)
+        } else {
+            DayResult::Failed
+        }
+    }
+}
+
+/// Show a grid of each day's result for a month or a year, this month by default.
+pub fn calendar(message: Message, user_id: Option<String>, period: Option<&str>, mode: Mode) {
+    let Some(user_id) = user_id.or_else(|| message.author.as_ref().map(|user| user.id.to_string()))
+    else {
+        return;
+    };
+
+    let today = Hank::datetime().date_naive();
+    let Some((title, start, end, whole_year)) = parse_period(period.unwrap_or("month"), today)
+    else {
+        Hank::respond(
+            "Usage: `wordle calendar [@user] [month|year|YYYY-MM|YYYY] [hard|normal]`".into(),
+            message,
+        );
+        return;
+    };
+
+    if start > today {
+        Hank::respond(format!("{} hasn't happened yet!", title), message);
+        return;
+    }
+    let end = end.min(today);
+
+    let puzzles = match find_puzzles_by_player_between(&user_id, day_offset(start), day_offset(end))
+    {
+        Ok(puzzles) => puzzles
+            .into_iter()
+            .filter(|row| mode.includes(row.puzzle.hard_mode))
+            .collect::<Vec<_>>(),
+        Err(e) => {
+            warn!("couldn't look up puzzles for {}: {}", user_id, e);
+            return;
+        }
+    };
+
+    let Some(last) = puzzles.last() else {
+        Hank::respond(
+            format!(
+                "<@{}> didn't play any Wordles{} in {}.",
+                user_id,
+                mode.title(),
+                title
+            ),
+            message,
+        );
+        return;
+    };
+
+    let results = puzzles
+        .iter()
+        .map(|row| (date(row.puzzle.day_offset), DayResult::from(row)))
+        .collect::<HashMap<_, _>>();
+    let days = start
+        .iter_days()
+        .take_while(|day| *day <= end)
+        .map(|day| (day, results.get(&day).copied().unwrap_or(DayResult::Missed)))
+        .collect::<Vec<_>>();
+
+    let solved = puzzles.iter().filter(|row| row.puzzle.solved).count();
+    let mut text = format!(
+        "**{}'s {}{}**\n{} solved, {} failed, {} missed\n\n",
+        last.submitter,
+        title,
+        mode.title(),
+        solved,
+        puzzles.len() - solved,
+        days.len() - puzzles.len()
+    );
+    if whole_year {
+        text.push_str(&render_by_month(&days));
+    } else {
+        text.push_str(&render_by_weekday(&days));
+    }
+    text.push_str(&format!("\n\n{}", LEGEND));
+
+    // @TODO send a rendered heatmap with the text as the fallback once hank messages support
+    // attachments.
+    Hank::respond(text, message);
+}
+
+/// The title, first and last day of the period, and whether it's a whole year.
+fn parse_period(period: &str, today: NaiveDate) -> Option<(String, NaiveDate, NaiveDate, bool)> {
+    let (start, whole_year) = match period {
+        "month" => (today.with_day(1)?, false),
+        "year" => (NaiveDate::from_ymd_opt(today.year(), 1, 1)?, true),
+        _ => match NaiveDate::parse_from_str(&format!("{}-01", period), "%Y-%m-%d") {
+            Ok(start) => (start, false),
+            Err(_) => (NaiveDate::from_ymd_opt(period.parse().ok()?, 1, 1)?, true),
+        },
+    };
+
+    Some(if whole_year {
+        (
+            start.year().to_string(),
+            start,
+            start.checked_add_months(Months::new(12))?.pred_opt()?,
+            true,
+        )
+    } else {
+        (
+            start.format("%B %Y").to_string(),
+            start,
+            start.checked_add_months(Months::new(1))?.pred_opt()?,
+            false,
+        )
+    })
+}
+
+/// A row per day of the week and a column per week, like GitHub's contribution graph.
+fn render_by_weekday(days: &[(NaiveDate, DayResult)]) -> String {
+    let Some((first, _)) = days.first() else {
+        return String::new();
+    };
+    let offset = first.weekday().num_days_from_monday() as usize;
+
+    ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"]
+        .iter()
+        .enumerate()
+        .map(|(weekday, label)| {
+            let row = (0..(offset + days.len()).div_ceil(7))
+                .map(|week| match (week * 7 + weekday).checked_sub(offset) {
+                    Some(i) if i < days.len() => emoji(days[i].1),
+                    _ => "➖",
+                })
+                .collect::<String>();
+            format!("`{}` {}", label, row)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// A row per month, since a whole year is too wide for chat as a single grid.
+fn render_by_month(days: &[(NaiveDate, DayResult)]) -> String {
+    let mut months: Vec<(NaiveDate, String)> = Vec::new();
+    for (day, result) in days {
+        match months.last_mut() {
+            Some((month, row)) if month.month() == day.month() => row.push_str(emoji(*result)),
+            _ => months.push((*day, emoji(*result).into())),
+        }
+    }
+
+    months
+        .iter()
+        .map(|(month, row)| format!("`{}` {}", month.format("%b"), row))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn emoji(result: DayResult) -> &'static str {
+    match result {
+        DayResult::Solved(1) => "🟪",
+        DayResult::Solved(2) => "🟦",
+        DayResult::Solved(3) => "🟩",
+        DayResult::Solved(4) => "🟨",
+        DayResult::Solved(5) => "🟧",
+        DayResult::Solved(_) => "🟫",
+        DayResult::Failed => "🟥",
+        DayResult::Missed => "⬛",
+    }
+}
+
+fn day_offset(date: NaiveDate) -> u32 {
+    (date - wordle_launch_date()).num_days().max(0) as u32
+}
+
+fn date(day_offset: u32) -> NaiveDate {
+    wordle_launch_date() + chrono::Duration::days(day_offset.into())
+}
+
+fn find_puzzles_by_player_between(player_id: &str, from: u32, to: u32) -> Result<Vec<PuzzleRow>> {
+    let query = "
+SELECT * FROM puzzle
+WHERE submitted_by = ? AND day_offset BETWEEN ? AND ?
+ORDER BY day_offset
+";
+    let statement = PreparedStatement::new(query)
+        .values([player_id.to_string(), from.to_string(), to.to_string()])
+        .build();
+
+    Hank::db_fetch::<PuzzleRow>(statement).map_err(|e| anyhow!(e))
+}
@@ -0,0 +1,422 @@
+use crate::play::{can_start_game, find_active_game, find_game_by_id, insert_game, GameRow};
+use crate::{is_direct_message, random_answer, send_to_channel};
+use anyhow::{anyhow, bail, Result};
+use hank_pdk::{warn, Hank};
+use hank_types::channel::ChannelKind;
+use hank_types::database::PreparedStatement;
+use hank_types::message::Message;
+use hank_types::user::User;
+use serde::Deserialize;
+use std::cmp::Ordering;
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct ChallengeRow {
+    id: u64,
+    channel_id: String,
+    challenger: String,
+    challenger_id: u64,
+    opponent: Option<String>,
+    opponent_id: u64,
+    solution: String,
+    created_at: chrono::DateTime<chrono::Local>,
+    challenger_game_id: Option<u64>,
+    opponent_game_id: Option<u64>,
+    winner_id: Option<u64>,
+    finished_at: Option<chrono::DateTime<chrono::Local>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChallengeRecordRow {
+    wins: u32,
+    losses: u32,
+    draws: u32,
+}
+
+pub fn install() {
+    let query = "
+CREATE TABLE IF NOT EXISTS challenge (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    channel_id TEXT NOT NULL,
+    challenger TEXT NOT NULL,
+    challenger_id INTEGER NOT NULL,
+    opponent TEXT,
+    opponent_id INTEGER NOT NULL,
+    solution TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    challenger_game_id INTEGER,
+    opponent_game_id INTEGER,
+    winner_id INTEGER,
+    finished_at TEXT
+);
+";
+    let _ = Hank::db_query(PreparedStatement::new(query).build());
+
+    let query = "
+CREATE TABLE IF NOT EXISTS challenge_record (
+    player_id INTEGER NOT NULL,
+    opponent_id INTEGER NOT NULL,
+    wins INTEGER NOT NULL,
+    losses INTEGER NOT NULL,
+    draws INTEGER NOT NULL,
+    PRIMARY KEY(player_id, opponent_id)
+);
+";
+    let _ = Hank::db_query(PreparedStatement::new(query).build());
+}
+
+/// Challenge another user to race on the same random word.
+pub fn challenge(message: Message, opponent_id: &str) {
+    let (Some(ref user), Some(ref channel)) = (&message.author, &message.channel) else {
+        return;
+    };
+
+    if channel.kind() != ChannelKind::ChatRoom {
+        Hank::respond(
+            "Challenges are issued in a channel so everyone can see the result!".into(),
+            message,
+        );
+        return;
+    }
+
+    let user_id = user.id.to_string();
+    if opponent_id == user_id {
+        Hank::respond("You can't challenge yourself!".into(), message);
+        return;
+    }
+
+    if let Err(e) = insert_challenge(user, &channel.id, opponent_id, &random_answer()) {
+        warn!("couldn't create challenge: {}", e);
+        return;
+    }
+
+    Hank::respond(
+        format!(
+            "<@{}>, {} has challenged you to a Wordle race! You'll both get the same word, fewest guesses wins and the fastest breaks a tie. DM me `wordle race` when you're ready to start your clock.",
+            opponent_id, user.name
+        ),
+        message,
+    );
+}
+
+/// Start the user's clock on their most recent challenge.
+pub fn race(message: Message) {
+    let Some(ref user) = message.author else {
+        return;
+    };
+
+    if !is_direct_message(&message) {
+        Hank::respond(
+            "DM me `wordle race` so your guesses stay secret!".into(),
+            message,
+        );
+        return;
+    }
+
+    let challenge = match find_unstarted_challenge(user) {
+        Ok(Some(challenge)) => challenge,
+        Ok(None) => {
+            Hank::respond(
+                "You don't have any challenges waiting, start one with `wordle challenge @user`."
+                    .into(),
+                message,
+            );
+            return;
+        }
+        Err(e) => {
+            warn!("couldn't look up {}'s challenges: {}", user.name, e);
+            return;
+        }
+    };
+
+    if !can_start_game(&message, user) {
+        return;
+    }
+
+    let game = insert_game(user, "challenge", 0, &challenge.solution, false)
+        .and_then(|_| find_active_game(user)?.ok_or(anyhow!("game wasn't created")));
+    let game = match game {
+        Ok(game) => game,
+        Err(e) => {
+            warn!("couldn't start {}'s challenge game: {}", user.name, e);
+            return;
+        }
+    };
+
+    if let Err(e) = set_challenge_game(&challenge, user, &game) {
+        warn!("couldn't link {}'s challenge game: {}", user.name, e);
+        return;
+    }
+
+    Hank::respond(
+        "Your clock is running! Make your guesses with `wordle guess WORD`.".into(),
+        message,
+    );
+}
+
+/// Called when a challenge game is finished, announcing the result once both players are done.
+pub fn game_finished(row: &GameRow) {
+    let challenge = match find_challenge_by_game(row) {
+        Ok(Some(challenge)) => challenge,
+        Ok(None) => return,
+        Err(e) => {
+            warn!("couldn't look up challenge for game {}: {}", row.id, e);
+            return;
+        }
+    };
+
+    let games = challenge
+        .challenger_game_id
+        .zip(challenge.opponent_game_id)
+        .map(|(challenger, opponent)| (find_game_by_id(challenger), find_game_by_id(opponent)));
+    let (challenger_game, opponent_game) = match games {
+        Some((Ok(Some(challenger)), Ok(Some(opponent))))
+            if challenger.finished_at.is_some() && opponent.finished_at.is_some() =>
+        {
+            (challenger, opponent)
+        }
+        _ => return,
+    };
+
+    let opponent = challenge.opponent.clone().unwrap_or_default();
+    let winner = match compare(&challenger_game, &opponent_game) {
+        Ordering::Less => Some((challenge.challenger_id, challenge.opponent_id)),
+        Ordering::Greater => Some((challenge.opponent_id, challenge.challenger_id)),
+        Ordering::Equal => None,
+    };
+
+    if let Err(e) = finish_challenge(&challenge, winner) {
+        warn!("couldn't finish challenge {}: {}", challenge.id, e);
+    }
+
+    let result = match winner {
+        Some((winner, _)) => format!("<@{}> wins!", winner),
+        None => "It's a draw!".into(),
+    };
+    send_to_channel(
+        &challenge.channel_id,
+        format!(
+            "**Wordle race: {} vs {}**\n{}\n{}\n\nThe word was **{}**. {}",
+            challenge.challenger,
+            opponent,
+            describe(&challenge.challenger, &challenger_game),
+            describe(&opponent, &opponent_game),
+            challenge.solution.to_uppercase(),
+            result
+        ),
+    );
+}
+
+/// Show the user's challenge record against another user.
+pub fn record(message: Message, opponent_id: &str) {
+    let Some(ref user) = message.author else {
+        return;
+    };
+
+    let statement = PreparedStatement::new(
+        "SELECT wins, losses, draws FROM challenge_record WHERE player_id = ? AND opponent_id = ?",
+    )
+    .values([user.id.to_string(), opponent_id.to_string()])
+    .build();
+
+    let record = match Hank::db_fetch::<ChallengeRecordRow>(statement) {
+        Ok(records) => records.into_iter().next(),
+        Err(e) => {
+            warn!("couldn't look up challenge record: {}", e);
+            return;
+        }
+    };
+
+    let response = match record {
+        Some(record) => format!(
+            "Your record against <@{}>: {} {}, {} {}, {} {}",
+            opponent_id,
+            record.wins,
+            pluralizer::pluralize("win", record.wins as isize, false),
+            record.losses,
+            pluralizer::pluralize("loss", record.losses as isize, false),
+            record.draws,
+            pluralizer::pluralize("draw", record.draws as isize, false),
+        ),
+        None => format!(
+            "You haven't finished a race against <@{}> yet.",
+            opponent_id
+        ),
+    };
+
+    Hank::respond(response, message);
+}
+
+/// Order two finished games, better first: solving beats not solving, then fewer guesses, then
+/// the faster time.
+fn compare(a: &GameRow, b: &GameRow) -> Ordering {
+    let (a_game, b_game) = (a.game(), b.game());
+
+    b_game.is_solved().cmp(&a_game.is_solved()).then_with(|| {
+        if a_game.is_solved() {
+            a_game
+                .guesses
+                .len()
+                .cmp(&b_game.guesses.len())
+                .then_with(|| elapsed(a).cmp(&elapsed(b)))
+        } else {
+            Ordering::Equal
+        }
+    })
+}
+
+fn elapsed(row: &GameRow) -> chrono::Duration {
+    row.finished_at.unwrap_or(row.started_at) - row.started_at
+}
+
+fn describe(name: &str, row: &GameRow) -> String {
+    let game = row.game();
+    let elapsed = elapsed(row);
+    let time = format!("{}m {}s", elapsed.num_minutes(), elapsed.num_seconds() % 60);
+
+    if game.is_solved() {
+        format!(
+            "{} solved it in {} ({})",
+            name,
+            pluralizer::pluralize("guess", game.guesses.len() as isize, true),
+            time
+        )
+    } else {
+        format!("{} didn't solve it ({})", name, time)
+    }
+}
+
+fn insert_challenge(
+    user: &User,
+    channel_id: &str,
+    opponent_id: &str,
+    solution: &str,
+) -> Result<()> {
+    let query = "
+INSERT INTO challenge (channel_id, challenger, challenger_id, opponent_id, solution, created_at)
+VALUES (?, ?, ?, ?, ?, ?)
+";
+    let statement = PreparedStatement::new(query)
+        .values([
+            channel_id.to_string(),
+            user.name.clone(),
+            user.id.to_string(),
+            opponent_id.to_string(),
+            solution.to_string(),
+            Hank::datetime().to_rfc3339(),
+        ])
+        .build();
+
+    Hank::db_query(statement)
+        .map(|_| ())
+        .map_err(|e| anyhow!(e))
+}
+
+fn set_challenge_game(challenge: &ChallengeRow, user: &User, game: &GameRow) -> Result<()> {
+    let user_id = user.id.to_string();
+    let is_challenger = challenge.challenger_id.to_string() == user_id;
+    let query = if is_challenger {
+        "UPDATE challenge SET challenger_game_id = ? WHERE id = ?"
+    } else {
+        "UPDATE challenge SET opponent_game_id = ?, opponent = ? WHERE id = ?"
+    };
+    let values = if is_challenger {
+        vec![game.id.to_string(), challenge.id.to_string()]
+    } else {
+        vec![
+            game.id.to_string(),
+            user.name.clone(),
+            challenge.id.to_string(),
+        ]
+    };
+
+    Hank::db_query(PreparedStatement::new(query).values(values).build())
+        .map(|_| ())
+        .map_err(|e| anyhow!(e))
+}
+
+fn finish_challenge(challenge: &ChallengeRow, winner: Option<(u64, u64)>) -> Result<()> {
+    let statement = PreparedStatement::new(
+        "UPDATE challenge SET winner_id = NULLIF(?, ''), finished_at = ? WHERE id = ?",
+    )
+    .values([
+        winner.map(|(w, _)| w.to_string()).unwrap_or_default(),
+        Hank::datetime().to_rfc3339(),
+        challenge.id.to_string(),
+    ])
+    .build();
+    Hank::db_query(statement).map_err(|e| anyhow!(e))?;
+
+    let (a, b) = (challenge.challenger_id, challenge.opponent_id);
+    let results = match winner {
+        Some((winner, loser)) => [(winner, loser, "wins"), (loser, winner, "losses")],
+        None => [(a, b, "draws"), (b, a, "draws")],
+    };
+    for (player, opponent, column) in results {
+        update_record(player, opponent, column)?;
+    }
+
+    Ok(())
+}
+
+fn update_record(player_id: u64, opponent_id: u64, column: &str) -> Result<()> {
+    let query = match column {
+        "wins" => {
+            "
+INSERT INTO challenge_record (player_id, opponent_id, wins, losses, draws) VALUES (?, ?, 1, 0, 0)
+ON CONFLICT(player_id, opponent_id) DO UPDATE SET wins = wins + 1
+"
+        }
+        "losses" => {
+            "
+INSERT INTO challenge_record (player_id, opponent_id, wins, losses, draws) VALUES (?, ?, 0, 1, 0)
+ON CONFLICT(player_id, opponent_id) DO UPDATE SET losses = losses + 1
+"
+        }
+        "draws" => {
+            "
+INSERT INTO challenge_record (player_id, opponent_id, wins, losses, draws) VALUES (?, ?, 0, 0, 1)
+ON CONFLICT(player_id, opponent_id) DO UPDATE SET draws = draws + 1
+"
+        }
+        _ => bail!("unknown challenge record column {}", column),
+    };
+    let statement = PreparedStatement::new(query)
+        .values([player_id.to_string(), opponent_id.to_string()])
+        .build();
+
+    Hank::db_query(statement)
+        .map(|_| ())
+        .map_err(|e| anyhow!(e))
+}
+
+fn find_unstarted_challenge(user: &User) -> Result<Option<ChallengeRow>> {
+    let query = "
+SELECT * FROM challenge
+WHERE finished_at IS NULL
+AND ((challenger_id = ? AND challenger_game_id IS NULL) OR (opponent_id = ? AND opponent_game_id IS NULL))
+ORDER BY created_at DESC
+LIMIT 1
+";
+    let statement = PreparedStatement::new(query)
+        .values([user.id.to_string(), user.id.to_string()])
+        .build();
+
+    Ok(Hank::db_fetch::<ChallengeRow>(statement)
+        .map_err(|e| anyhow!(e))?
+        .into_iter()
+        .next())
+}
+
+fn find_challenge_by_game(row: &GameRow) -> Result<Option<ChallengeRow>> {
+    let statement = PreparedStatement::new(
+        "SELECT * FROM challenge WHERE challenger_game_id = ? OR opponent_game_id = ?",
+    )
+    .values([row.id.to_string(), row.id.to_string()])
+    .build();
+
+    Ok(Hank::db_fetch::<ChallengeRow>(statement)
+        .map_err(|e| anyhow!(e))?
+        .into_iter()
+        .next())
+}
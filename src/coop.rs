@@ -0,0 +1,376 @@
+use crate::wordle::Game;
+use crate::{deserialize_bool, random_answer};
+use anyhow::{anyhow, Result};
+use hank_pdk::{warn, Hank};
+use hank_types::channel::{Channel, ChannelKind};
+use hank_types::database::PreparedStatement;
+use hank_types::message::Message;
+use hank_types::user::User;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct CoopGameRow {
+    id: u64,
+    channel_id: String,
+    solution: String,
+    started_by: u64,
+    started_at: chrono::DateTime<chrono::Local>,
+    finished_at: Option<chrono::DateTime<chrono::Local>>,
+    #[serde(deserialize_with = "deserialize_bool")]
+    solved: bool,
+    winner: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct CoopGuessRow {
+    game_id: u64,
+    guesser: String,
+    guess: String,
+}
+
+pub fn install() {
+    let query = "
+CREATE TABLE IF NOT EXISTS coop_game (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    channel_id TEXT NOT NULL,
+    solution TEXT NOT NULL,
+    started_by INTEGER NOT NULL,
+    started_at TEXT NOT NULL,
+    finished_at TEXT,
+    solved INTEGER NOT NULL,
+    winner INTEGER
+);
+";
+    let _ = Hank::db_query(PreparedStatement::new(query).build());
+
+    let query = "
+CREATE TABLE IF NOT EXISTS coop_guess (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    game_id INTEGER NOT NULL,
+    guesser TEXT NOT NULL,
+    guessed_by INTEGER NOT NULL,
+    guess TEXT NOT NULL,
+    guessed_at TEXT NOT NULL
+);
+";
+    let _ = Hank::db_query(PreparedStatement::new(query).build());
+}
+
+/// Start a co-op game that everybody in the channel can guess in.
+pub fn start(message: Message) {
+    let (Some(ref user), Some(ref channel)) = (&message.author, &message.channel) else {
+        return;
+    };
+
+    if channel.kind() != ChannelKind::ChatRoom {
+        Hank::respond(
+            "Co-op games are played in a channel so everyone can join in!".into(),
+            message,
+        );
+        return;
+    }
+
+    match find_active_game(channel) {
+        Ok(Some(row)) => {
+            let guesses = find_guesses(row.id).unwrap_or_default();
+            Hank::respond(
+                format!(
+                    "There's already a co-op game in progress!\n\n{}",
+                    render(&row, &guesses)
+                ),
+                message,
+            );
+            return;
+        }
+        Ok(None) => (),
+        Err(e) => {
+            warn!("couldn't look up the co-op game for {}: {}", channel.id, e);
+            return;
+        }
+    }
+
+    if let Err(e) = insert_game(user, channel, &random_answer()) {
+        warn!("couldn't start a co-op game: {}", e);
+        return;
+    }
+
+    Hank::respond(
+        "A co-op Wordle has started! Everyone can guess with `wordle coop guess WORD`.".into(),
+        message,
+    );
+}
+
+/// Submit a guess to the channel's co-op game.
+pub fn guess(message: Message, guess: &str) {
+    let (Some(ref user), Some(ref channel)) = (&message.author, &message.channel) else {
+        return;
+    };
+
+    let row = match find_active_game(channel) {
+        Ok(Some(row)) => row,
+        Ok(None) => {
+            Hank::respond(
+                "There's no co-op game in progress, start one with `wordle coop start`.".into(),
+                message,
+            );
+            return;
+        }
+        Err(e) => {
+            warn!("couldn't look up the co-op game for {}: {}", channel.id, e);
+            return;
+        }
+    };
+
+    let mut guesses = match find_guesses(row.id) {
+        Ok(guesses) => guesses,
+        Err(e) => {
+            warn!("couldn't look up guesses for co-op game {}: {}", row.id, e);
+            return;
+        }
+    };
+
+    let word = guess.trim().to_lowercase();
+    if guesses.iter().any(|g| g.guess == word) {
+        Hank::respond(
+            format!("{} has already been guessed!", word.to_uppercase()),
+            message,
+        );
+        return;
+    }
+
+    let mut game = Game {
+        solution: row.solution.clone(),
+        guesses: guesses.iter().map(|g| g.guess.clone()).collect(),
+        hard_mode: false,
+    };
+    if let Err(e) = game.guess(&word) {
+        Hank::respond(format!("Invalid guess: {}", e), message);
+        return;
+    }
+
+    if let Err(e) = insert_guess(&row, user, &word) {
+        warn!("couldn't save co-op guess: {}", e);
+        return;
+    }
+    guesses.push(CoopGuessRow {
+        game_id: row.id,
+        guesser: user.name.clone(),
+        guess: word,
+    });
+
+    let mut response = render(&row, &guesses);
+    if game.is_finished() {
+        if let Err(e) = finish_game(&row, game.is_solved().then_some(user)) {
+            warn!("couldn't finish co-op game {}: {}", row.id, e);
+        }
+
+        response = if game.is_solved() {
+            format!(
+                "{}\n\n{} got it in {}! Nice teamwork everyone.",
+                response,
+                user.name,
+                guesses.len()
+            )
+        } else {
+            format!(
+                "{}\n\nOut of guesses! The word was **{}**.",
+                response,
+                row.solution.to_uppercase()
+            )
+        };
+    }
+
+    Hank::respond(response, message);
+}
+
+/// Summarize the co-op games played in the channel.
+pub fn stats(message: Message) {
+    let Some(ref channel) = message.channel else {
+        return;
+    };
+
+    let games = match find_finished_games(channel) {
+        Ok(games) => games,
+        Err(e) => {
+            warn!("couldn't look up co-op games for {}: {}", channel.id, e);
+            return;
+        }
+    };
+
+    if games.is_empty() {
+        Hank::respond(
+            "No co-op games have been finished here yet, start one with `wordle coop start`."
+                .into(),
+            message,
+        );
+        return;
+    }
+
+    let mut solved = 0;
+    let mut solved_guesses = 0;
+    let mut contributions: HashMap<String, u32> = HashMap::new();
+    let mut wins: HashMap<String, u32> = HashMap::new();
+    for game in &games {
+        let guesses = find_guesses(game.id).unwrap_or_default();
+        for guess in &guesses {
+            *contributions.entry(guess.guesser.clone()).or_default() += 1;
+        }
+
+        if game.solved {
+            solved += 1;
+            solved_guesses += guesses.len();
+            if let Some(winner) = guesses.last() {
+                *wins.entry(winner.guesser.clone()).or_default() += 1;
+            }
+        }
+    }
+
+    let mut response = format!(
+        "**Co-op Wordle Stats**\n{} games played, {} solved ({:.0}%)",
+        games.len(),
+        solved,
+        solved as f64 / games.len() as f64 * 100.0
+    );
+    if solved > 0 {
+        response.push_str(&format!(
+            ", {:.1} guesses on average",
+            solved_guesses as f64 / solved as f64
+        ));
+    }
+    response.push_str(&format!(
+        "\n\n**Winning guesses**\n{}\n\n**Guesses contributed**\n{}",
+        top(wins),
+        top(contributions)
+    ));
+
+    Hank::respond(response, message);
+}
+
+fn top(counts: HashMap<String, u32>) -> String {
+    let mut counts = counts.into_iter().collect::<Vec<_>>();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    if counts.is_empty() {
+        return "Nobody yet!".into();
+    }
+
+    counts
+        .iter()
+        .take(5)
+        .enumerate()
+        .map(|(i, (name, count))| format!("{}. {} - {}", i + 1, name, count))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn render(row: &CoopGameRow, guesses: &[CoopGuessRow]) -> String {
+    let game = Game {
+        solution: row.solution.clone(),
+        guesses: guesses.iter().map(|g| g.guess.clone()).collect(),
+        hard_mode: false,
+    };
+
+    let board = game
+        .render(Default::default())
+        .lines()
+        .zip(guesses)
+        .map(|(line, guess)| format!("{} - {}", line, guess.guesser))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!("{}\n\n{}", board, game.keyboard())
+}
+
+fn insert_game(user: &User, channel: &Channel, solution: &str) -> Result<()> {
+    let query = "
+INSERT INTO coop_game (channel_id, solution, started_by, started_at, solved)
+VALUES (?, ?, ?, ?, 'false')
+";
+    let statement = PreparedStatement::new(query)
+        .values([
+            channel.id.clone(),
+            solution.to_string(),
+            user.id.to_string(),
+            Hank::datetime().to_rfc3339(),
+        ])
+        .build();
+
+    Hank::db_query(statement)
+        .map(|_| ())
+        .map_err(|e| anyhow!(e))
+}
+
+fn insert_guess(row: &CoopGameRow, user: &User, guess: &str) -> Result<()> {
+    let query = "
+INSERT INTO coop_guess (game_id, guesser, guessed_by, guess, guessed_at)
+VALUES (?, ?, ?, ?, ?)
+";
+    let statement = PreparedStatement::new(query)
+        .values([
+            row.id.to_string(),
+            user.name.clone(),
+            user.id.to_string(),
+            guess.to_string(),
+            Hank::datetime().to_rfc3339(),
+        ])
+        .build();
+
+    Hank::db_query(statement)
+        .map(|_| ())
+        .map_err(|e| anyhow!(e))
+}
+
+fn finish_game(row: &CoopGameRow, winner: Option<&User>) -> Result<()> {
+    let query = "
+UPDATE coop_game
+SET finished_at = ?, solved = ?, winner = NULLIF(?, '')
+WHERE id = ?
+";
+    let statement = PreparedStatement::new(query)
+        .values([
+            Hank::datetime().to_rfc3339(),
+            winner.is_some().to_string(),
+            winner.map(|w| w.id.to_string()).unwrap_or_default(),
+            row.id.to_string(),
+        ])
+        .build();
+
+    Hank::db_query(statement)
+        .map(|_| ())
+        .map_err(|e| anyhow!(e))
+}
+
+fn find_active_game(channel: &Channel) -> Result<Option<CoopGameRow>> {
+    let statement = PreparedStatement::new(
+        "SELECT * FROM coop_game WHERE channel_id = ? AND finished_at IS NULL LIMIT 1",
+    )
+    .values([channel.id.clone()])
+    .build();
+
+    Ok(Hank::db_fetch::<CoopGameRow>(statement)
+        .map_err(|e| anyhow!(e))?
+        .into_iter()
+        .next())
+}
+
+fn find_finished_games(channel: &Channel) -> Result<Vec<CoopGameRow>> {
+    let statement = PreparedStatement::new(
+        "SELECT * FROM coop_game WHERE channel_id = ? AND finished_at IS NOT NULL",
+    )
+    .values([channel.id.clone()])
+    .build();
+
+    Hank::db_fetch::<CoopGameRow>(statement).map_err(|e| anyhow!(e))
+}
+
+fn find_guesses(game_id: u64) -> Result<Vec<CoopGuessRow>> {
+    let statement =
+        PreparedStatement::new("SELECT * FROM coop_guess WHERE game_id = ? ORDER BY id ASC")
+            .values([game_id.to_string()])
+            .build();
+
+    Hank::db_fetch::<CoopGuessRow>(statement).map_err(|e| anyhow!(e))
+}
@@ -0,0 +1,246 @@
+use crate::play::{begin, find_finished_games};
+use crate::wordle::{is_valid_guess, MAX_GUESSES};
+use crate::{find_user_theme, is_direct_message, random_u64};
+use anyhow::{anyhow, Result};
+use hank_pdk::{warn, Hank};
+use hank_types::database::PreparedStatement;
+use hank_types::message::Message;
+use hank_types::user::User;
+use serde::Deserialize;
+
+/// Letters and digits used for puzzle codes, leaving out the ones that are easily confused.
+const CODE_ALPHABET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+const CODE_LENGTH: usize = 6;
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct CustomPuzzleRow {
+    id: u32,
+    code: String,
+    creator: String,
+    creator_id: u64,
+    solution: String,
+    created_at: chrono::DateTime<chrono::Local>,
+}
+
+pub fn install() {
+    let query = "
+CREATE TABLE IF NOT EXISTS custom_puzzle (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    code TEXT NOT NULL UNIQUE,
+    creator TEXT NOT NULL,
+    creator_id INTEGER NOT NULL,
+    solution TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
+";
+    let _ = Hank::db_query(PreparedStatement::new(query).build());
+}
+
+/// Create a puzzle with the user's own secret word.
+pub fn create(message: Message, word: &str) {
+    let Some(ref user) = message.author else {
+        return;
+    };
+
+    if !is_direct_message(&message) {
+        Hank::respond(
+            "DM me `wordle create WORD` so your word stays a secret!".into(),
+            message,
+        );
+        return;
+    }
+
+    let word = word.trim().to_lowercase();
+    if !is_valid_guess(&word) {
+        Hank::respond(
+            format!("{} isn't in the word list", word.to_uppercase()),
+            message,
+        );
+        return;
+    }
+
+    let code = match new_code().and_then(|code| insert_custom_puzzle(user, &code, &word)) {
+        Ok(code) => code,
+        Err(e) => {
+            warn!("couldn't create {}'s custom puzzle: {}", user.name, e);
+            return;
+        }
+    };
+
+    Hank::respond(
+        format!(
+            "Your puzzle is ready! Anyone can play it by DMing me `wordle play {}`, and you can see how everyone did with `wordle results {}`.",
+            code, code
+        ),
+        message,
+    );
+}
+
+/// Play somebody's custom puzzle.
+pub fn play(message: Message, code: &str) {
+    let Some(ref user) = message.author else {
+        return;
+    };
+
+    let puzzle = match find_custom_puzzle(code) {
+        Ok(Some(puzzle)) => puzzle,
+        Ok(None) => {
+            Hank::respond(
+                format!("There's no puzzle with the code {}", code.to_uppercase()),
+                message,
+            );
+            return;
+        }
+        Err(e) => {
+            warn!("couldn't look up custom puzzle {}: {}", code, e);
+            return;
+        }
+    };
+
+    if user.id == puzzle.creator_id.to_string() {
+        Hank::respond(
+            "You can't play your own puzzle, share the code with someone else!".into(),
+            message,
+        );
+        return;
+    }
+
+    begin(
+        message,
+        "custom",
+        puzzle.id,
+        &format!("{}'s puzzle {}", puzzle.creator, puzzle.code),
+        &puzzle.solution,
+        false,
+    );
+}
+
+/// Show the creator of a custom puzzle how everyone did.
+pub fn results(message: Message, code: &str) {
+    let Some(ref user) = message.author else {
+        return;
+    };
+
+    let puzzle = match find_custom_puzzle(code) {
+        Ok(Some(puzzle)) if user.id == puzzle.creator_id.to_string() => puzzle,
+        Ok(_) => {
+            Hank::respond(
+                format!(
+                    "You haven't created a puzzle with the code {}",
+                    code.to_uppercase()
+                ),
+                message,
+            );
+            return;
+        }
+        Err(e) => {
+            warn!("couldn't look up custom puzzle {}: {}", code, e);
+            return;
+        }
+    };
+
+    let games = match find_finished_games("custom", puzzle.id) {
+        Ok(games) => games,
+        Err(e) => {
+            warn!(
+                "couldn't look up games for custom puzzle {}: {}",
+                puzzle.code, e
+            );
+            return;
+        }
+    };
+
+    if games.is_empty() {
+        Hank::respond(
+            format!("Nobody has finished puzzle {} yet.", puzzle.code),
+            message,
+        );
+        return;
+    }
+
+    let theme = find_user_theme(&user.id.to_string());
+    let boards = games
+        .iter()
+        .map(|row| {
+            let game = row.game();
+            format!(
+                "**{}** {}/{}\n{}",
+                row.player,
+                if game.is_solved() {
+                    game.guesses.len().to_string()
+                } else {
+                    "X".into()
+                },
+                MAX_GUESSES,
+                game.board(theme).render(theme)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let solved = games.iter().filter(|row| row.game().is_solved()).count();
+
+    Hank::respond(
+        format!(
+            "**Puzzle {}** ({})\n{} of {} solved it\n\n{}",
+            puzzle.code,
+            puzzle.solution.to_uppercase(),
+            solved,
+            games.len(),
+            boards
+        ),
+        message,
+    );
+}
+
+/// A random code that isn't in use yet. Codes are picked independently of the word so they can't
+/// give it away.
+fn new_code() -> Result<String> {
+    for _ in 0..5 {
+        let mut seed = random_u64();
+        let code = (0..CODE_LENGTH)
+            .map(|_| {
+                let c = CODE_ALPHABET[(seed % CODE_ALPHABET.len() as u64) as usize];
+                seed /= CODE_ALPHABET.len() as u64;
+                c as char
+            })
+            .collect::<String>();
+
+        if find_custom_puzzle(&code)?.is_none() {
+            return Ok(code);
+        }
+    }
+
+    Err(anyhow!("couldn't find an unused puzzle code"))
+}
+
+fn insert_custom_puzzle(user: &User, code: &str, solution: &str) -> Result<String> {
+    let query = "
+INSERT INTO custom_puzzle (code, creator, creator_id, solution, created_at)
+VALUES (?, ?, ?, ?, ?)
+";
+    let statement = PreparedStatement::new(query)
+        .values([
+            code.to_string(),
+            user.name.clone(),
+            user.id.to_string(),
+            solution.to_string(),
+            Hank::datetime().to_rfc3339(),
+        ])
+        .build();
+
+    Hank::db_query(statement)
+        .map(|_| code.to_string())
+        .map_err(|e| anyhow!(e))
+}
+
+fn find_custom_puzzle(code: &str) -> Result<Option<CustomPuzzleRow>> {
+    let statement = PreparedStatement::new("SELECT * FROM custom_puzzle WHERE code = ?")
+        .values([code.to_uppercase()])
+        .build();
+
+    Ok(Hank::db_fetch::<CustomPuzzleRow>(statement)
+        .map_err(|e| anyhow!(e))?
+        .into_iter()
+        .next())
+}
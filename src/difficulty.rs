@@ -0,0 +1,167 @@
+use crate::get_current_puzzle;
+use crate::history::parse_day;
+use crate::mode::Mode;
+use crate::wordle::FAIL_SCORE;
+use anyhow::{anyhow, Result};
+use hank_pdk::{warn, Hank};
+use hank_types::database::PreparedStatement;
+use hank_types::message::Message;
+use serde::Deserialize;
+
+/// Results for each day in the mode, alongside how each player did compared to their own average.
+fn difficulty_query(mode: Mode) -> String {
+    format!(
+        "
+WITH scored AS (
+    SELECT submitted_by, day_offset, solved, CASE WHEN solved = 'true' THEN attempts ELSE {} END AS score
+    FROM puzzle
+    WHERE {}
+), player AS (
+    SELECT submitted_by, AVG(score) AS average FROM scored GROUP BY submitted_by
+)
+SELECT
+    s.day_offset,
+    COUNT(*) AS players,
+    AVG(s.score) AS average,
+    AVG(CASE WHEN s.solved = 'true' THEN 0.0 ELSE 1.0 END) AS fail_rate,
+    AVG(s.score - p.average) AS relative
+FROM scored s
+JOIN player p ON p.submitted_by = s.submitted_by
+GROUP BY s.day_offset
+",
+        FAIL_SCORE,
+        mode.condition("hard_mode")
+    )
+}
+
+#[derive(Debug, Deserialize)]
+struct DifficultyRow {
+    day_offset: u32,
+    players: u32,
+    average: f64,
+    fail_rate: f64,
+    /// How many more attempts than usual players needed.
+    relative: f64,
+}
+
+impl DifficultyRow {
+    fn rating(&self) -> &'static str {
+        match self.relative {
+            r if r < -0.75 => "very easy",
+            r if r < -0.25 => "easy",
+            r if r < 0.25 => "average",
+            r if r < 0.75 => "hard",
+            _ => "brutal",
+        }
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "Wordle #{} was **{}**: {:.2} attempts on average ({:+.2} compared to usual), {:.0}% failed, {}",
+            self.day_offset,
+            self.rating(),
+            self.average,
+            self.relative,
+            self.fail_rate * 100.0,
+            pluralizer::pluralize("player", self.players as isize, true)
+        )
+    }
+}
+
+/// Show how difficult a puzzle was in the mode, today's by default.
+pub fn difficulty(message: Message, day: Option<&str>, mode: Mode) {
+    let day_offset = match day {
+        Some(day) => match parse_day(day) {
+            Some(day_offset) => day_offset,
+            None => {
+                Hank::respond(
+                    format!("{} isn't a Wordle number or a date like 2022-01-31", day),
+                    message,
+                );
+                return;
+            }
+        },
+        None => get_current_puzzle(false).days_since_launch,
+    };
+
+    let response = match describe(day_offset, mode) {
+        Ok(Some(description)) => format!("{}{}", description, mode.title()),
+        Ok(None) => format!(
+            "Nobody has posted Wordle #{}{} yet.",
+            day_offset,
+            mode.title()
+        ),
+        Err(e) => {
+            warn!(
+                "couldn't look up difficulty of Wordle #{}: {}",
+                day_offset, e
+            );
+            return;
+        }
+    };
+
+    Hank::respond(response, message);
+}
+
+/// The hardest puzzles played so far.
+pub fn hardest(message: Message, mode: Mode) {
+    let statement = PreparedStatement::new(format!(
+        "{} HAVING COUNT(*) >= 2 ORDER BY relative DESC LIMIT 5",
+        difficulty_query(mode)
+    ))
+    .build();
+
+    let rows = match Hank::db_fetch::<DifficultyRow>(statement) {
+        Ok(rows) => rows,
+        Err(e) => {
+            warn!("couldn't look up the hardest puzzles: {}", e);
+            return;
+        }
+    };
+
+    if rows.is_empty() {
+        Hank::respond(
+            "Not enough people have played to say which puzzles were hardest.".into(),
+            message,
+        );
+        return;
+    }
+
+    let mut response = format!("**Hardest Wordles{}**\n", mode.title());
+    for (i, row) in rows.iter().enumerate() {
+        response.push_str(&format!("{}. {}\n", i + 1, row.describe()));
+    }
+
+    Hank::respond(response, message);
+}
+
+/// Describe the difficulty of a puzzle in the mode if anybody has played it.
+pub fn describe(day_offset: u32, mode: Mode) -> Result<Option<String>> {
+    let statement = PreparedStatement::new(format!(
+        "{} HAVING s.day_offset = ?",
+        difficulty_query(mode)
+    ))
+    .values([day_offset.to_string()])
+    .build();
+
+    Ok(Hank::db_fetch::<DifficultyRow>(statement)
+        .map_err(|e| anyhow!(e))?
+        .first()
+        .map(DifficultyRow::describe))
+}
+
+/// Describe the hardest puzzle between the day offsets that more than one person played in the
+/// mode.
+pub fn describe_hardest(from: u32, to: u32, mode: Mode) -> Result<Option<String>> {
+    let statement = PreparedStatement::new(format!(
+        "{} HAVING s.day_offset BETWEEN ? AND ? AND COUNT(*) >= 2 ORDER BY relative DESC LIMIT 1",
+        difficulty_query(mode)
+    ))
+    .values([from.to_string(), to.to_string()])
+    .build();
+
+    Ok(Hank::db_fetch::<DifficultyRow>(statement)
+        .map_err(|e| anyhow!(e))?
+        .first()
+        .map(DifficultyRow::describe))
+}
@@ -0,0 +1,171 @@
+use crate::{find_puzzle_by_user_and_day_offset, get_current_puzzle, is_direct_message};
+use anyhow::{anyhow, Result};
+use hank_pdk::{warn, Hank};
+use hank_types::database::PreparedStatement;
+use hank_types::message::Message;
+use hank_types::user::User;
+use serde::Deserialize;
+use std::collections::HashSet;
+
+#[derive(Debug, Deserialize)]
+struct HintRow {
+    user_id: u64,
+    level: u8,
+}
+
+pub fn install() {
+    let query = "
+CREATE TABLE IF NOT EXISTS hint (
+    user_id INTEGER NOT NULL,
+    day_offset INTEGER NOT NULL,
+    level INTEGER NOT NULL,
+    hinted_at TEXT NOT NULL,
+    PRIMARY KEY(user_id, day_offset)
+);
+";
+    let _ = Hank::db_query(PreparedStatement::new(query).build());
+}
+
+/// Give the user the next hint for today's puzzle, each one giving away a little more.
+pub fn hint(message: Message) {
+    let Some(ref user) = message.author else {
+        return;
+    };
+
+    if !is_direct_message(&message) {
+        Hank::respond(
+            "DM me `wordle hint` so you don't spoil the puzzle for everyone!".into(),
+            message,
+        );
+        return;
+    }
+
+    let current = get_current_puzzle(false);
+    if current.solution.is_empty() {
+        Hank::respond(
+            "I couldn't fetch today's Wordle, try again in a bit.".into(),
+            message,
+        );
+        return;
+    }
+
+    match find_puzzle_by_user_and_day_offset(user, current.days_since_launch) {
+        Ok(Some(_)) => {
+            Hank::respond(
+                "You've already posted today's Wordle, no more hints until tomorrow!".into(),
+                message,
+            );
+            return;
+        }
+        Ok(None) => (),
+        Err(e) => {
+            warn!("couldn't look up {}'s result: {}", user.name, e);
+            return;
+        }
+    }
+
+    let level = match find_hint_level(user, current.days_since_launch) {
+        Ok(level) => level.unwrap_or_default() + 1,
+        Err(e) => {
+            warn!("couldn't look up {}'s hints: {}", user.name, e);
+            return;
+        }
+    };
+
+    let Some(hint) = clue(&current.solution, level) else {
+        Hank::respond(
+            "That's all the hints I've got, you can do it!".into(),
+            message,
+        );
+        return;
+    };
+
+    if let Err(e) = set_hint_level(user, current.days_since_launch, level) {
+        warn!("couldn't record {}'s hint: {}", user.name, e);
+        return;
+    }
+
+    Hank::respond(
+        format!(
+            "Hint {}: {}\n\nUsing a hint puts a 💡 next to your result on today's leaderboard.",
+            level, hint
+        ),
+        message,
+    );
+}
+
+/// The users that have used a hint for the puzzle.
+pub fn find_hinted_users(day_offset: u32) -> Result<HashSet<u64>> {
+    let statement = PreparedStatement::new("SELECT user_id, level FROM hint WHERE day_offset = ?")
+        .values([day_offset.to_string()])
+        .build();
+
+    Ok(Hank::db_fetch::<HintRow>(statement)
+        .map_err(|e| anyhow!(e))?
+        .into_iter()
+        .map(|row| row.user_id)
+        .collect())
+}
+
+fn clue(solution: &str, level: u8) -> Option<String> {
+    let solution = solution.to_ascii_uppercase();
+    let letters = solution.chars().collect::<Vec<_>>();
+
+    match level {
+        1 => {
+            let vowels = letters.iter().filter(|c| "AEIOU".contains(**c)).count();
+            Some(format!(
+                "It has {}.",
+                pluralizer::pluralize("vowel", vowels as isize, true)
+            ))
+        }
+        2 => {
+            let repeated = letters
+                .iter()
+                .enumerate()
+                .any(|(i, c)| letters[..i].contains(c));
+            Some(if repeated {
+                "It has a repeated letter.".into()
+            } else {
+                "Every letter is different.".into()
+            })
+        }
+        3 => letters.first().map(|c| format!("It starts with {}.", c)),
+        4 => letters.last().map(|c| format!("It ends with {}.", c)),
+        _ => None,
+    }
+}
+
+fn find_hint_level(user: &User, day_offset: u32) -> Result<Option<u8>> {
+    let statement = PreparedStatement::new(
+        "SELECT user_id, level FROM hint WHERE user_id = ? AND day_offset = ?",
+    )
+    .values([user.id.to_string(), day_offset.to_string()])
+    .build();
+
+    Ok(Hank::db_fetch::<HintRow>(statement)
+        .map_err(|e| anyhow!(e))?
+        .into_iter()
+        .next()
+        .map(|row| row.level))
+}
+
+fn set_hint_level(user: &User, day_offset: u32, level: u8) -> Result<()> {
+    let query = "
+INSERT INTO hint (user_id, day_offset, level, hinted_at)
+VALUES (?, ?, ?, ?)
+ON CONFLICT(user_id, day_offset) DO UPDATE SET level = excluded.level
+";
+    let statement = PreparedStatement::new(query)
+        .values([
+            user.id.to_string(),
+            day_offset.to_string(),
+            level.to_string(),
+            Hank::datetime().to_rfc3339(),
+        ])
+        .build();
+
+    Hank::db_query(statement)
+        .map(|_| ())
+        .map_err(|e| anyhow!(e))
+}
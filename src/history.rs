@@ -0,0 +1,268 @@
+use crate::wordle::{add_answers, to_word, WORD_LENGTH};
+use crate::{get_current_puzzle, request_puzzle, wordle_launch_date, CurrentPuzzle};
+use anyhow::{anyhow, Result};
+use hank_pdk::{info, warn, Hank};
+use hank_types::database::PreparedStatement;
+use hank_types::message::Message;
+use serde::Deserialize;
+use std::collections::HashSet;
+
+/// How many missing days are fetched each time the history is backfilled, so the NYT endpoint
+/// isn't hammered.
+const BACKFILL_BATCH: usize = 30;
+
+#[derive(Debug, Deserialize)]
+pub struct DailyPuzzleRow {
+    pub day_offset: u32,
+    pub print_date: chrono::NaiveDate,
+    pub solution: String,
+}
+
+pub fn install() {
+    let query = "
+CREATE TABLE IF NOT EXISTS daily_puzzle (
+    day_offset INTEGER NOT NULL PRIMARY KEY,
+    print_date TEXT NOT NULL,
+    solution TEXT NOT NULL,
+    editor TEXT NOT NULL,
+    fetched_at TEXT NOT NULL
+);
+";
+    let _ = Hank::db_query(PreparedStatement::new(query).build());
+}
+
+/// Remember a puzzle fetched from NYT.
+pub fn record(puzzle: &CurrentPuzzle) {
+    let query = "
+INSERT INTO daily_puzzle (day_offset, print_date, solution, editor, fetched_at)
+VALUES (?, ?, ?, ?, ?)
+ON CONFLICT(day_offset) DO NOTHING
+";
+    let statement = PreparedStatement::new(query)
+        .values([
+            puzzle.days_since_launch.to_string(),
+            puzzle.print_date.to_string(),
+            puzzle.solution.to_ascii_lowercase(),
+            puzzle.editor.clone(),
+            Hank::datetime().to_rfc3339(),
+        ])
+        .build();
+
+    if let Err(e) = Hank::db_query(statement) {
+        warn!(
+            "couldn't record Wordle #{}: {}",
+            puzzle.days_since_launch, e
+        );
+    }
+
+    add_answers([puzzle.solution.as_str()]);
+}
+
+/// Treat every recorded answer as a possible one, the bundled list is missing the answers NYT has
+/// added since it was made.
+pub fn load_answers() {
+    let statement =
+        PreparedStatement::new("SELECT day_offset, print_date, solution FROM daily_puzzle").build();
+
+    match Hank::db_fetch::<DailyPuzzleRow>(statement) {
+        Ok(puzzles) => add_answers(puzzles.iter().map(|puzzle| puzzle.solution.as_str())),
+        Err(e) => warn!("couldn't look up recorded answers: {}", e),
+    }
+}
+
+/// Fetch a batch of past puzzles that aren't in the history yet.
+pub fn backfill() {
+    let today = get_current_puzzle(false).days_since_launch;
+    let known = match find_past_puzzles() {
+        Ok(puzzles) => puzzles
+            .into_iter()
+            .map(|puzzle| puzzle.day_offset)
+            .collect::<HashSet<_>>(),
+        Err(e) => {
+            warn!("couldn't look up the puzzle history: {}", e);
+            return;
+        }
+    };
+
+    let missing = (0..today)
+        .filter(|day_offset| !known.contains(day_offset))
+        .take(BACKFILL_BATCH)
+        .collect::<Vec<_>>();
+    if missing.is_empty() {
+        return;
+    }
+
+    info!("Backfilling {} puzzles...", missing.len());
+    for day_offset in missing {
+        // Puzzles are recorded as they're fetched.
+        let date = wordle_launch_date() + chrono::Duration::days(day_offset.into());
+        if let Err(e) = request_puzzle(&date, 1) {
+            warn!("couldn't backfill Wordle #{}: {}", day_offset, e);
+        }
+    }
+}
+
+/// Reveal a past answer by puzzle number or date.
+pub fn answer(message: Message, day: &str) {
+    let today = get_current_puzzle(false).days_since_launch;
+    let Some(day_offset) = parse_day(day) else {
+        Hank::respond(
+            format!("{} isn't a Wordle number or a date like 2022-01-31", day),
+            message,
+        );
+        return;
+    };
+
+    if day_offset >= today {
+        Hank::respond("Nice try! I only reveal past answers.".into(), message);
+        return;
+    }
+
+    let solution = match find_past_puzzle(day_offset) {
+        Ok(Some(puzzle)) => Some(puzzle.solution),
+        Ok(None) => {
+            let date = wordle_launch_date() + chrono::Duration::days(day_offset.into());
+            request_puzzle(&date, 2).ok().map(|puzzle| puzzle.solution)
+        }
+        Err(e) => {
+            warn!("couldn't look up Wordle #{}: {}", day_offset, e);
+            return;
+        }
+    };
+
+    let date = wordle_launch_date() + chrono::Duration::days(day_offset.into());
+    Hank::respond(
+        match solution {
+            Some(solution) => format!(
+                "The answer to Wordle #{} ({}) was **{}**",
+                day_offset,
+                date,
+                solution.to_uppercase()
+            ),
+            None => format!("I couldn't find the answer to Wordle #{}", day_offset),
+        },
+        message,
+    );
+}
+
+/// Check whether a word has already been an answer.
+pub fn used(message: Message, word: &str) {
+    let word = word.trim().to_lowercase();
+    if to_word(&word).is_err() {
+        Hank::respond(
+            format!("{} isn't a {} letter word", word, WORD_LENGTH),
+            message,
+        );
+        return;
+    }
+
+    let puzzles = match find_past_puzzles() {
+        Ok(puzzles) => puzzles,
+        Err(e) => {
+            warn!("couldn't look up the puzzle history: {}", e);
+            return;
+        }
+    };
+
+    let response = match puzzles.iter().find(|puzzle| puzzle.solution == word) {
+        Some(puzzle) => format!(
+            "Yes, {} was the answer to Wordle #{} on {}",
+            word.to_uppercase(),
+            puzzle.day_offset,
+            puzzle.print_date
+        ),
+        None => format!(
+            "{} hasn't been an answer yet (out of the {} past answers I know)",
+            word.to_uppercase(),
+            puzzles.len()
+        ),
+    };
+
+    Hank::respond(response, message);
+}
+
+/// How often each letter has appeared in past answers, overall and in each position.
+pub fn letters(message: Message) {
+    let puzzles = match find_past_puzzles() {
+        Ok(puzzles) => puzzles,
+        Err(e) => {
+            warn!("couldn't look up the puzzle history: {}", e);
+            return;
+        }
+    };
+
+    if puzzles.is_empty() {
+        Hank::respond("I don't know any past answers yet.".into(), message);
+        return;
+    }
+
+    let mut overall = [0u32; 26];
+    let mut positions = [[0u32; 26]; WORD_LENGTH];
+    for word in puzzles
+        .iter()
+        .filter_map(|puzzle| to_word(&puzzle.solution).ok())
+    {
+        for (i, letter) in word.iter().enumerate() {
+            overall[(letter - b'a') as usize] += 1;
+            positions[i][(letter - b'a') as usize] += 1;
+        }
+    }
+
+    let top = |counts: &[u32; 26], n: usize| {
+        let mut letters = (0..26u8).zip(counts.iter().copied()).collect::<Vec<_>>();
+        letters.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        letters
+            .iter()
+            .take(n)
+            .map(|(letter, count)| format!("{} {}", (b'A' + letter) as char, count))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    let mut response = format!(
+        "**Letter frequency across {} past answers**\n{}\n",
+        puzzles.len(),
+        top(&overall, 10)
+    );
+    for (i, counts) in positions.iter().enumerate() {
+        response.push_str(&format!("\nLetter {}: {}", i + 1, top(counts, 5)));
+    }
+
+    Hank::respond(response, message);
+}
+
+/// A puzzle number, e.g. `1,234` or `#1234`, or the date it was published.
+pub fn parse_day(day: &str) -> Option<u32> {
+    if let Ok(date) = chrono::NaiveDate::parse_from_str(day, "%Y-%m-%d") {
+        return (date - wordle_launch_date()).num_days().try_into().ok();
+    }
+
+    day.replace([',', '#'], "").parse().ok()
+}
+
+/// Every answer before today's that has been recorded.
+pub fn find_past_puzzles() -> Result<Vec<DailyPuzzleRow>> {
+    let statement = PreparedStatement::new(
+        "SELECT day_offset, print_date, solution FROM daily_puzzle WHERE day_offset < ? ORDER BY day_offset ASC",
+    )
+    .values([get_current_puzzle(false).days_since_launch.to_string()])
+    .build();
+
+    Hank::db_fetch::<DailyPuzzleRow>(statement).map_err(|e| anyhow!(e))
+}
+
+fn find_past_puzzle(day_offset: u32) -> Result<Option<DailyPuzzleRow>> {
+    let statement = PreparedStatement::new(
+        "SELECT day_offset, print_date, solution FROM daily_puzzle WHERE day_offset = ? AND day_offset < ?",
+    )
+    .values([
+        day_offset.to_string(),
+        get_current_puzzle(false).days_since_launch.to_string(),
+    ])
+    .build();
+
+    Ok(Hank::db_fetch::<DailyPuzzleRow>(statement)
+        .map_err(|e| anyhow!(e))?
+        .into_iter()
+        .next())
+}
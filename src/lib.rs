@@ -8,14 +8,36 @@ use hank_types::database::PreparedStatement;
 use hank_types::message::Message;
 use hank_types::plugin::{CommandContext, Metadata};
 use hank_types::user::User;
+use mode::{Mode, Ranking};
 use oxford_join::OxfordJoin;
 use pluralizer::pluralize;
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer};
 use std::collections::HashMap;
 use std::sync::{Arc, OnceLock};
-use wordle::Puzzle;
-
+use wordle::{Puzzle, Theme};
+
+mod achievement;
+mod calendar;
+mod challenge;
+mod coop;
+mod custom;
+mod difficulty;
+mod hint;
+mod history;
+mod mode;
+mod patterns;
+mod play;
+mod points;
+mod rating;
+mod recap;
+mod reconstruct;
+mod season;
+mod skill;
+mod solve;
+mod stats;
+mod versus;
 mod wordle;
+mod wrapped;
 
 #[plugin_fn]
 pub fn plugin() -> FnResult<()> {
@@ -77,6 +99,36 @@ CREATE TABLE IF NOT EXISTS puzzle (
 );
 ";
     let _ = Hank::db_query(PreparedStatement::new(query).build());
+
+    let query = "
+CREATE TABLE IF NOT EXISTS user_preference (
+    user_id INTEGER NOT NULL,
+    key TEXT NOT NULL,
+    value TEXT NOT NULL,
+    PRIMARY KEY(user_id, key)
+);
+";
+    let _ = Hank::db_query(PreparedStatement::new(query).build());
+
+    let query = "
+CREATE TABLE IF NOT EXISTS setting (
+    key TEXT NOT NULL PRIMARY KEY,
+    value TEXT NOT NULL
+);
+";
+    let _ = Hank::db_query(PreparedStatement::new(query).build());
+
+    play::install();
+    coop::install();
+    challenge::install();
+    custom::install();
+    hint::install();
+    history::install();
+    rating::install();
+    points::install();
+    season::install();
+    achievement::install();
+    skill::install();
 }
 
 // @TODO consider watching for messages that contain the solution and track who says the daily
@@ -97,7 +149,7 @@ impl CurrentPuzzle {
         let today = Hank::datetime();
         let wordle_launch_day = today
             .offset()
-            .with_ymd_and_hms(2021, 6, 19, 0, 0, 0)
+            .from_local_datetime(&wordle_launch_date().into())
             .unwrap();
         let days_since_launch = Hank::datetime()
             .signed_duration_since(wordle_launch_day)
@@ -113,33 +165,47 @@ impl CurrentPuzzle {
     }
 }
 
-fn get_current_puzzle(refresh: bool) -> Guard<Arc<CurrentPuzzle>> {
-    static CURRENT_PUZZLE: OnceLock<ArcSwap<CurrentPuzzle>> = OnceLock::new();
+/// The day the first Wordle was published, puzzle numbers count the days since.
+fn wordle_launch_date() -> chrono::NaiveDate {
+    chrono::NaiveDate::from_ymd_opt(2021, 6, 19).expect("launch date should be valid")
+}
 
-    fn request_puzzle(retries: u8) -> Result<CurrentPuzzle> {
-        let req = HttpRequest::new(format!(
-            "https://www.nytimes.com/svc/wordle/v2/{}.json",
-            Hank::datetime().date_naive(),
-        ));
-        match http::request::<String>(&req, None)?.json::<CurrentPuzzle>() {
-            Ok(puzzle) => Ok(puzzle),
-            Err(e) => {
-                warn!(
-                    "Error getting current puzzle, retrying {} more time(s): {}",
-                    retries - 1,
-                    e
-                );
-                if retries > 1 {
-                    request_puzzle(retries - 1)
-                } else {
-                    Err(e)
-                }
+/// Request the puzzle published on the given date.
+fn request_puzzle(date: &chrono::NaiveDate, retries: u8) -> Result<CurrentPuzzle> {
+    let req = HttpRequest::new(format!(
+        "https://www.nytimes.com/svc/wordle/v2/{}.json",
+        date,
+    ));
+    match http::request::<String>(&req, None)?.json::<CurrentPuzzle>() {
+        Ok(puzzle) => {
+            history::record(&puzzle);
+            Ok(puzzle)
+        }
+        Err(e) => {
+            warn!(
+                "Error getting puzzle for {}, retrying {} more time(s): {}",
+                date,
+                retries - 1,
+                e
+            );
+            if retries > 1 {
+                request_puzzle(date, retries - 1)
+            } else {
+                Err(e)
             }
         }
     }
+}
+
+fn get_current_puzzle(refresh: bool) -> Guard<Arc<CurrentPuzzle>> {
+    static CURRENT_PUZZLE: OnceLock<ArcSwap<CurrentPuzzle>> = OnceLock::new();
+
+    fn request_current_puzzle(retries: u8) -> Result<CurrentPuzzle> {
+        request_puzzle(&Hank::datetime().date_naive(), retries)
+    }
 
     fn refresh_puzzle(retries: u8) -> Guard<Arc<CurrentPuzzle>> {
-        match request_puzzle(retries) {
+        match request_current_puzzle(retries) {
             Ok(puzzle) => {
                 CURRENT_PUZZLE.get().unwrap().store(puzzle.into());
                 CURRENT_PUZZLE.get().unwrap().load()
@@ -163,7 +229,7 @@ fn get_current_puzzle(refresh: bool) -> Guard<Arc<CurrentPuzzle>> {
         let _ = refresh_puzzle(2);
     }
 
-    let current = CURRENT_PUZZLE.get_or_init(|| match request_puzzle(2) {
+    let current = CURRENT_PUZZLE.get_or_init(|| match request_current_puzzle(2) {
         Ok(puzzle) => ArcSwap::from_pointee(puzzle),
         Err(e) => {
             warn!("Failed to init current puzzle after 2 retries, falling back to calculated puzzle: {}", e);
@@ -188,7 +254,19 @@ fn announce_yesterdays_winners() {
         return;
     };
 
+    // Knowing how hard the puzzle was puts everyone's results in context, even when nobody solved
+    // it.
+    let yesterday = Hank::datetime().date_naive() - chrono::Duration::days(1);
+    let difficulty = (yesterday - wordle_launch_date())
+        .num_days()
+        .try_into()
+        .ok()
+        .and_then(|day_offset| difficulty::describe(day_offset, Mode::All).ok().flatten());
+
     if winners.is_empty() {
+        if let Some(difficulty) = difficulty {
+            announce(format!("Nobody solved yesterday's Wordle!\n{}", difficulty));
+        }
         return;
     }
 
@@ -212,32 +290,33 @@ fn announce_yesterdays_winners() {
         (6, "6 attempts, phew!"),
     ]);
 
-    let content = format!(
+    let mut content = format!(
         "Congratulations to {} on being the top {} yesterday! <:limesDab:795850581725020250> {}",
         winners.oxford_and(),
         pluralize("Wordler", count as isize, false),
         comments.get(&attempts).expect("we should have a comment")
     );
+    if let Some(difficulty) = difficulty {
+        content.push_str(&format!("\n{}", difficulty));
+    }
+
+    announce(content);
+}
 
+fn announce(content: String) {
     // @TODO how should the announcement channel get set? ideally it's not hardcoded.
     // do we just need a .wordle settings accouncement_channel #general
     // @note ideally i'd like to have a settings interface built in to hank
     // @note i wonder if bots know who owns them/invited them to the server? then on the daily
     // announcement, if there's no announcemnet_channel set, it can DM the owner to let them know
-    Hank::send_message(Message {
-        channel: Some(Channel {
-            kind: ChannelKind::ChatRoom.into(),
-            id: "664538126613741590".to_string(),
-            ..Default::default()
-        }),
-        content,
-        ..Default::default()
-    });
+    send_to_channel("664538126613741590", content);
 }
 
 pub fn initialize() {
     info!("Initializing...");
 
+    history::load_answers();
+
     // Cache the current days puzzle.
     let _ = get_current_puzzle(false);
 
@@ -247,27 +326,253 @@ pub fn initialize() {
     });
 
     Hank::cron("0 0 9 * * *", announce_yesterdays_winners);
+
+    // Update ratings once yesterday's results are in.
+    Hank::cron("0 5 0 * * *", rating::close_days);
+
+    // Crown the champion once a season is over.
+    Hank::cron("0 10 0 * * *", season::close_seasons);
+
+    // Slowly fill in the answers to past puzzles.
+    Hank::cron("0 30 * * * *", history::backfill);
+
+    // Recap the week and month once they're over, after the daily winners.
+    Hank::cron("0 15 9 * * Mon", recap::announce_weekly);
+    Hank::cron("0 20 9 1 * *", recap::announce_monthly);
+
+    // Look back on the year once it's over.
+    Hank::cron("0 0 12 1 1 *", wrapped::announce_last_year);
 }
 
+/// Commands that can be limited to hard or normal mode results by ending them with `hard` or
+/// `normal`, along with the leaderboard itself.
+const MODE_COMMANDS: [&str; 13] = [
+    "points",
+    "skill",
+    "stats",
+    "calendar",
+    "patterns",
+    "vs",
+    "hardest",
+    "wrapped",
+    "rating",
+    "ratings",
+    "difficulty",
+    "recap",
+    "seasons",
+];
+
 pub fn wordle_chat_commands(_context: CommandContext, message: Message) {
-    let leaderboard =
-        find_puzzles_by_date_ordered_by_rank(&Hank::datetime().date_naive()).unwrap_or_default();
+    let mut args = command_args(&message.content);
+    let mode = if args.len() == 1
+        || args
+            .first()
+            .is_some_and(|command| MODE_COMMANDS.contains(&command.as_str()))
+    {
+        Mode::take(&mut args)
+    } else {
+        Mode::All
+    };
+    match *args
+        .iter()
+        .map(AsRef::as_ref)
+        .collect::<Vec<_>>()
+        .as_slice()
+    {
+        ["play"] => play::start(message, false),
+        ["play", "hard"] => play::start(message, true),
+        ["play", code] => custom::play(message, code),
+        ["guess", guess] => play::guess(message, guess),
+        ["archive", day_offset] | ["archive", day_offset, "hard"] => {
+            match day_offset.replace([',', '#'], "").parse() {
+                Ok(day_offset) => play::start_archive(message, day_offset, args.len() == 3),
+                Err(_) => Hank::respond(format!("{} isn't a Wordle number", day_offset), message),
+            }
+        }
+        ["coop"] | ["coop", "start"] => coop::start(message),
+        ["coop", "guess", guess] => coop::guess(message, guess),
+        ["coop", "stats"] => coop::stats(message),
+        ["challenge", "record", opponent] => match parse_mention(opponent) {
+            Some(opponent) => challenge::record(message, &opponent),
+            None => Hank::respond("Usage: `wordle challenge record @user`".into(), message),
+        },
+        ["challenge", opponent] => match parse_mention(opponent) {
+            Some(opponent) => challenge::challenge(message, &opponent),
+            None => Hank::respond("Usage: `wordle challenge @user`".into(), message),
+        },
+        ["race"] => challenge::race(message),
+        ["hint"] => hint::hint(message),
+        ["analyze", ref guesses @ ..] if !guesses.is_empty() => {
+            skill::analyze_guesses(message, guesses)
+        }
+        ["skill"] => skill::leaderboard(message, mode),
+        ["answer", day] => history::answer(message, day),
+        ["used", word] => history::used(message, word),
+        ["letters"] => history::letters(message),
+        ["difficulty"] => difficulty::difficulty(message, None, mode),
+        ["difficulty", day] => difficulty::difficulty(message, Some(day), mode),
+        ["hardest"] => difficulty::hardest(message, mode),
+        ["rating"] => rating::rating(message, mode),
+        ["ratings"] => rating::leaderboard(message, mode),
+        ["points"] => points::leaderboard(message, "season", mode),
+        ["points", period] => points::leaderboard(message, period, mode),
+        ["badges"] => achievement::badges(message, None),
+        ["badges", user] => match parse_mention(user) {
+            Some(user) => achievement::badges(message, Some(user)),
+            None => Hank::respond("Usage: `wordle badges [@user]`".into(), message),
+        },
+        ["seasons"] => season::seasons(message, mode),
+        ["stats"] => stats::stats(message, None, mode),
+        ["stats", user] => match parse_mention(user) {
+            Some(user) => stats::stats(message, Some(user), mode),
+            None => Hank::respond("Usage: `wordle stats [@user]`".into(), message),
+        },
+        ["calendar"] => calendar::calendar(message, None, None, mode),
+        ["calendar", arg] => match parse_mention(arg) {
+            Some(user) => calendar::calendar(message, Some(user), None, mode),
+            None => calendar::calendar(message, None, Some(arg), mode),
+        },
+        ["calendar", user, period] => match parse_mention(user) {
+            Some(user) => calendar::calendar(message, Some(user), Some(period), mode),
+            None => Hank::respond(
+                "Usage: `wordle calendar [@user] [month|year|YYYY-MM|YYYY] [hard|normal]`".into(),
+                message,
+            ),
+        },
+        ["patterns"] => patterns::patterns(message, None, mode),
+        ["patterns", user] => match parse_mention(user) {
+            Some(user) => patterns::patterns(message, Some(user), mode),
+            None => Hank::respond("Usage: `wordle patterns [@user]`".into(), message),
+        },
+        ["wall"] => stats::wall(message),
+        ["recap", kind] => recap::recap(message, kind, mode),
+        ["recap", kind, "on"] => recap::set_enabled(message, kind, true),
+        ["recap", kind, "off"] => recap::set_enabled(message, kind, false),
+        ["wrapped"] => wrapped::wrapped(message, None, mode),
+        ["wrapped", year] => wrapped::wrapped(message, Some(year), mode),
+        ["season", "length", length] => season::set_length(message, length),
+        ["tiebreaker", "on"] => mode::set_tiebreaker(message, true),
+        ["tiebreaker", "off"] => mode::set_tiebreaker(message, false),
+        ["scoring"] => points::scoring(message),
+        ["scoring", name, value] => points::set_weight(message, name, value),
+        ["vs", a, b] => match (parse_mention(a), parse_mention(b)) {
+            (Some(a), Some(b)) => versus::versus(message, &a, &b, mode),
+            _ => Hank::respond("Usage: `wordle vs @user @user`".into(), message),
+        },
+        ["reconstruct", "on"] => reconstruct::set_opt_in(message, true),
+        ["reconstruct", "off"] => reconstruct::set_opt_in(message, false),
+        ["solve", "hard", ref guesses @ ..] => solve::solve(message, guesses, true),
+        ["solve", ref guesses @ ..] => solve::solve(message, guesses, false),
+        ["create", word] => custom::create(message, word),
+        ["results", code] => custom::results(message, code),
+        _ => respond_with_leaderboard(message, mode),
+    }
+}
+
+/// The arguments given to the wordle command, lowercased and without the command itself.
+fn command_args(content: &str) -> Vec<String> {
+    let mut args = content.split_whitespace().map(str::to_lowercase).peekable();
+    if args.peek().is_some_and(|command| {
+        command.trim_start_matches(|c: char| !c.is_alphanumeric()) == "wordle"
+    }) {
+        args.next();
+    }
+
+    args.collect()
+}
+
+/// Pick a random answer for games that aren't today's puzzle.
+fn random_answer() -> String {
+    let answers = wordle::answers();
+
+    String::from_utf8_lossy(&answers[(random_u64() % answers.len() as u64) as usize]).into_owned()
+}
+
+/// A random number seeded from the current time.
+fn random_u64() -> u64 {
+    // Mix the timestamp (splitmix64) so calls made close together get unrelated numbers.
+    let mut seed = (Hank::datetime().timestamp_nanos_opt().unwrap_or_default() as u64)
+        .wrapping_add(0x9e3779b97f4a7c15);
+    seed = (seed ^ (seed >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    seed = (seed ^ (seed >> 27)).wrapping_mul(0x94d049bb133111eb);
+    seed ^ (seed >> 31)
+}
+
+/// The user id from a mention like `<@1234>` or `<@!1234>`.
+fn parse_mention(mention: &str) -> Option<String> {
+    let id = mention
+        .strip_prefix("<@")?
+        .strip_suffix('>')?
+        .trim_start_matches('!');
+
+    (!id.is_empty() && id.chars().all(|c| c.is_ascii_digit())).then(|| id.to_string())
+}
+
+fn send_to_channel(channel_id: &str, content: String) {
+    Hank::send_message(Message {
+        channel: Some(Channel {
+            kind: ChannelKind::ChatRoom.into(),
+            id: channel_id.to_string(),
+            ..Default::default()
+        }),
+        content,
+        ..Default::default()
+    });
+}
+
+fn send_direct_message(user: &User, content: String) {
+    Hank::send_message(Message {
+        channel: Some(Channel {
+            kind: ChannelKind::DirectMessage.into(),
+            id: user.id.to_string(),
+            ..Default::default()
+        }),
+        content,
+        ..Default::default()
+    });
+}
+
+fn is_direct_message(message: &Message) -> bool {
+    message
+        .channel
+        .as_ref()
+        .is_some_and(|channel| channel.kind() == ChannelKind::DirectMessage)
+}
+
+fn respond_with_leaderboard(message: Message, mode: Mode) {
+    let leaderboard = find_puzzles_by_date_ordered_by_rank(&Hank::datetime().date_naive(), mode)
+        .unwrap_or_default();
     if leaderboard.is_empty() {
         return;
     }
 
-    let mut response = String::from("**Today's Top Wordlers**\n");
+    let hinted =
+        hint::find_hinted_users(get_current_puzzle(false).days_since_launch).unwrap_or_default();
+
+    let mut response = format!("**Today's Top Wordlers{}**\n", mode.title());
     for (i, entry) in leaderboard.iter().enumerate() {
         let dab = if entry.rank == 1 {
             "<:limesDab:795850581725020250>"
         } else {
             ""
         };
+        // Not an asterisk, which Wordle already uses to mark hard mode results.
+        let bulb = if hinted.contains(&entry.row.submitted_by) {
+            " 💡"
+        } else {
+            ""
+        };
         response.push_str(&format!(
-            "{}. {} - {}/6 {}\n",
-            i, entry.row.submitter, entry.row.puzzle.attempts, dab
+            "{}. {} - {}/6{} {}\n",
+            i, entry.row.submitter, entry.row.puzzle.attempts, bulb, dab
         ));
     }
+    if leaderboard
+        .iter()
+        .any(|entry| hinted.contains(&entry.row.submitted_by))
+    {
+        response.push_str("\n💡 used a hint\n");
+    }
 
     Hank::respond(response, message)
 }
@@ -301,7 +606,18 @@ pub fn handle_message(message: Message) {
     }
 
     match insert_puzzle(user, &puzzle) {
-        Ok(_) => Hank::react("✅", message),
+        Ok(_) => {
+            // Remember the theme the user plays with so boards can be echoed back in it.
+            if Theme::identified_by(&message.content) {
+                if let Err(e) = set_user_preference(user, "theme", &puzzle.board.theme.to_string())
+                {
+                    warn!("couldn't save theme preference for {}: {}", user.name, e);
+                }
+            }
+
+            Hank::react("✅", message.clone());
+            reconstruct::respond_to_board(message, &puzzle);
+        }
         Err(e) => {
             match e {
                 InsertPuzzleError::UniqueConstraint(fields) => {
@@ -334,6 +650,7 @@ pub fn handle_message(message: Message) {
     }
 }
 
+#[derive(Debug)]
 enum InsertPuzzleError {
     UnknownError(String),
     UniqueConstraint(Vec<String>),
@@ -365,6 +682,14 @@ VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
 
     let res = Hank::db_query(statement);
     if res.is_ok() {
+        // Everyone's points for the day can change with a new result, e.g. the daily winner.
+        if let Err(e) = points::recompute(puzzle.day_offset, puzzle.day_offset) {
+            warn!(
+                "couldn't compute points for Wordle #{}: {}",
+                puzzle.day_offset, e
+            );
+        }
+        achievement::evaluate(user);
         return Ok(());
     }
 
@@ -382,6 +707,18 @@ VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
     }
 }
 
+fn find_puzzle_by_user_and_day_offset(user: &User, day_offset: u32) -> Result<Option<PuzzleRow>> {
+    let statement =
+        PreparedStatement::new("SELECT * FROM puzzle WHERE submitted_by = ? AND day_offset = ?")
+            .values([user.id.to_string(), day_offset.to_string()])
+            .build();
+
+    Ok(Hank::db_fetch::<PuzzleRow>(statement)
+        .map_err(|e| anyhow!(e))?
+        .into_iter()
+        .next())
+}
+
 fn find_puzzles() -> Result<Vec<PuzzleRow>> {
     let statement = PreparedStatement::new("SELECT * FROM puzzle").build();
     Hank::db_fetch::<PuzzleRow>(statement).map_err(|e| anyhow!(e))
@@ -401,13 +738,19 @@ fn find_yesterdays_winners() -> Result<Vec<PuzzleRow>> {
 }
 
 fn find_puzzles_by_date_and_rank(date: &chrono::NaiveDate, rank: u8) -> Result<Vec<PuzzleRow>> {
-    let query = "
+    let query = format!(
+        "
 SELECT * 
-FROM (SELECT *, RANK() OVER (ORDER BY attempts ASC) AS rank FROM puzzle WHERE submitted_date = ?)
+FROM (
+    SELECT *, RANK() OVER (ORDER BY {}) AS rank
+    FROM puzzle
+    WHERE submitted_date = ? AND solved = 'true'
+)
 WHERE rank = CAST(? AS INTEGER)
-AND solved = 'true'
 ORDER BY submitted_at ASC
-";
+",
+        Ranking::current().order_by()
+    );
     let statement = PreparedStatement::new(query)
         .values([date.to_string(), rank.to_string()])
         .build();
@@ -415,13 +758,23 @@ ORDER BY submitted_at ASC
     Hank::db_fetch::<PuzzleRow>(statement).map_err(|e| anyhow!(e))
 }
 
-fn find_puzzles_by_date_ordered_by_rank(date: &chrono::NaiveDate) -> Result<Vec<RankedPuzzleRow>> {
-    let query = "
+fn find_puzzles_by_date_ordered_by_rank(
+    date: &chrono::NaiveDate,
+    mode: Mode,
+) -> Result<Vec<RankedPuzzleRow>> {
+    let query = format!(
+        "
 SELECT * 
-FROM (SELECT *, RANK() OVER (ORDER BY attempts ASC) AS rank FROM puzzle WHERE submitted_date = ?)
-WHERE solved = 'true'
+FROM (
+    SELECT *, RANK() OVER (ORDER BY {}) AS rank
+    FROM puzzle
+    WHERE submitted_date = ? AND solved = 'true' AND {}
+)
 ORDER BY rank, submitted_at ASC
-";
+",
+        Ranking::current().order_by(),
+        mode.condition("hard_mode")
+    );
     let statement = PreparedStatement::new(query)
         .values([date.to_string()])
         .build();
@@ -436,3 +789,112 @@ fn find_puzzles_by_date(date: &chrono::NaiveDate) -> Result<Vec<PuzzleRow>> {
 
     Hank::db_fetch::<PuzzleRow>(statement).map_err(|e| anyhow!(e))
 }
+
+fn find_puzzles_between(
+    start: chrono::NaiveDate,
+    end: chrono::NaiveDate,
+) -> Result<Vec<PuzzleRow>> {
+    let statement = PreparedStatement::new(
+        "SELECT * FROM puzzle WHERE submitted_date BETWEEN ? AND ? ORDER BY day_offset",
+    )
+    .values([start.to_string(), end.to_string()])
+    .build();
+
+    Hank::db_fetch::<PuzzleRow>(statement).map_err(|e| anyhow!(e))
+}
+
+#[derive(Debug, Deserialize)]
+struct PreferenceRow {
+    value: String,
+}
+
+fn set_user_preference(user: &User, key: &str, value: &str) -> Result<()> {
+    let query = "
+INSERT INTO user_preference (user_id, key, value)
+VALUES (?, ?, ?)
+ON CONFLICT(user_id, key) DO UPDATE SET value = excluded.value
+";
+    let statement = PreparedStatement::new(query)
+        .values([user.id.to_string(), key.to_string(), value.to_string()])
+        .build();
+
+    Hank::db_query(statement)
+        .map(|_| ())
+        .map_err(|e| anyhow!(e))
+}
+
+fn find_user_preference(user_id: &str, key: &str) -> Result<Option<String>> {
+    let statement =
+        PreparedStatement::new("SELECT value FROM user_preference WHERE user_id = ? AND key = ?")
+            .values([user_id.to_string(), key.to_string()])
+            .build();
+
+    Ok(Hank::db_fetch::<PreferenceRow>(statement)
+        .map_err(|e| anyhow!(e))?
+        .into_iter()
+        .next()
+        .map(|row| row.value))
+}
+
+/// Whether the message is from someone allowed to change server-wide settings, telling them so if
+/// it isn't. Admins are listed by user id, separated by commas, in `WORDLE_ADMINS` when the plugin
+/// is built.
+fn check_admin(message: &Message) -> bool {
+    let is_admin = message.author.as_ref().is_some_and(|user| {
+        option_env!("WORDLE_ADMINS")
+            .unwrap_or_default()
+            .split(',')
+            .any(|id| id.trim() == user.id)
+    });
+    if !is_admin {
+        Hank::respond(
+            "Only Wordle admins can change that.".into(),
+            message.clone(),
+        );
+    }
+
+    is_admin
+}
+
+fn set_setting(key: &str, value: &str) -> Result<()> {
+    let query = "
+INSERT INTO setting (key, value)
+VALUES (?, ?)
+ON CONFLICT(key) DO UPDATE SET value = excluded.value
+";
+    let statement = PreparedStatement::new(query)
+        .values([key.to_string(), value.to_string()])
+        .build();
+
+    Hank::db_query(statement)
+        .map(|_| ())
+        .map_err(|e| anyhow!(e))
+}
+
+fn find_setting(key: &str) -> Result<Option<String>> {
+    let statement = PreparedStatement::new("SELECT value FROM setting WHERE key = ?")
+        .values([key.to_string()])
+        .build();
+
+    Ok(Hank::db_fetch::<PreferenceRow>(statement)
+        .map_err(|e| anyhow!(e))?
+        .into_iter()
+        .next()
+        .map(|row| row.value))
+}
+
+/// The theme a user last shared a board with, falling back to the default dark theme.
+fn find_user_theme(user_id: &str) -> Theme {
+    find_user_preference(user_id, "theme")
+        .ok()
+        .flatten()
+        .and_then(|theme| theme.parse().ok())
+        .unwrap_or_default()
+}
+
+/// Booleans are stored as `true`/`false` text, so they need parsing when read back.
+fn deserialize_bool<'de, D: Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
+    String::deserialize(deserializer)?
+        .parse()
+        .map_err(serde::de::Error::custom)
+}
@@ -0,0 +1,161 @@
+use crate::wordle::{score, Puzzle};
+use crate::{check_admin, find_setting, points, set_setting};
+use hank_pdk::{warn, Hank};
+use hank_types::message::Message;
+
+/// Which results a leaderboard or stat looks at.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Mode {
+    #[default]
+    All,
+    Hard,
+    Normal,
+}
+
+impl Mode {
+    pub const EVERY: [Mode; 3] = [Mode::All, Mode::Hard, Mode::Normal];
+
+    /// Take the mode off the end of a command's arguments if it has one.
+    pub fn take(args: &mut Vec<String>) -> Mode {
+        let mode = match args.last().map(String::as_str) {
+            Some("hard") => Mode::Hard,
+            Some("normal") => Mode::Normal,
+            _ => return Mode::All,
+        };
+        args.pop();
+
+        mode
+    }
+
+    pub fn includes(&self, hard_mode: bool) -> bool {
+        match self {
+            Mode::All => true,
+            Mode::Hard => hard_mode,
+            Mode::Normal => !hard_mode,
+        }
+    }
+
+    /// A condition on the hard_mode column, for use in a WHERE clause.
+    pub fn condition(&self, column: &str) -> String {
+        match self {
+            Mode::All => "1 = 1".into(),
+            Mode::Hard => format!("{} = 'true'", column),
+            Mode::Normal => format!("{} = 'false'", column),
+        }
+    }
+
+    /// How the mode is stored in tables kept separately for each mode.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Mode::All => "all",
+            Mode::Hard => "hard",
+            Mode::Normal => "normal",
+        }
+    }
+
+    /// Added to the end of titles so it's clear which results are shown.
+    pub fn title(&self) -> &'static str {
+        match self {
+            Mode::All => "",
+            Mode::Hard => " (Hard Mode)",
+            Mode::Normal => " (Normal Mode)",
+        }
+    }
+}
+
+/// Whether hard mode results rank above normal mode results with as many attempts.
+fn tiebreaker() -> bool {
+    find_setting("hard_mode_tiebreaker")
+        .ok()
+        .flatten()
+        .is_some_and(|enabled| enabled == "true")
+}
+
+/// Turn the hard mode tiebreaker on or off.
+pub fn set_tiebreaker(message: Message, enabled: bool) {
+    if !check_admin(&message) {
+        return;
+    }
+
+    // The daily winner bonus depends on the tiebreaker, so points are worked out again.
+    if let Err(e) = set_setting("hard_mode_tiebreaker", &enabled.to_string())
+        .and_then(|_| points::recompute(0, u32::MAX))
+    {
+        warn!("couldn't change the hard mode tiebreaker: {}", e);
+        return;
+    }
+
+    Hank::respond(
+        if enabled {
+            "Hard mode results now rank above normal mode results with as many attempts.".into()
+        } else {
+            "Hard mode results now tie with normal mode results with as many attempts.".into()
+        },
+        message,
+    );
+}
+
+/// How results on the same day rank against each other: an X loses to any solve, fewer attempts
+/// win, and with the tiebreaker on a hard mode result beats a normal mode one with as many
+/// attempts. Anything that decides who did better on a day goes through here, in Rust or SQL.
+#[derive(Clone, Copy, Debug)]
+pub struct Ranking {
+    tiebreaker: bool,
+}
+
+impl Ranking {
+    /// The ranking with the tiebreaker as it's currently set.
+    pub fn current() -> Ranking {
+        Ranking {
+            tiebreaker: tiebreaker(),
+        }
+    }
+
+    /// A key to sort results by, best first.
+    pub fn key(&self, solved: bool, attempts: u32, hard_mode: bool) -> (u32, bool) {
+        (score(solved, attempts), self.tiebreaker && !hard_mode)
+    }
+
+    pub fn puzzle_key(&self, puzzle: &Puzzle) -> (u32, bool) {
+        self.key(puzzle.solved, puzzle.attempts, puzzle.hard_mode)
+    }
+
+    /// The ORDER BY for ranking solved results, best first.
+    pub fn order_by(&self) -> &'static str {
+        if self.tiebreaker {
+            "attempts ASC, CASE WHEN hard_mode = 'true' THEN 0 ELSE 1 END ASC"
+        } else {
+            "attempts ASC"
+        }
+    }
+
+    /// A condition that the solved result in the `better` table ranks above the solved result in
+    /// the `worse` one.
+    pub fn outranks(&self, better: &str, worse: &str) -> String {
+        if self.tiebreaker {
+            format!(
+                "({better}.attempts < {worse}.attempts OR ({better}.attempts = {worse}.attempts AND {better}.hard_mode = 'true' AND {worse}.hard_mode = 'false'))"
+            )
+        } else {
+            format!("{better}.attempts < {worse}.attempts")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranks_results() {
+        let ranking = Ranking { tiebreaker: false };
+        assert!(ranking.key(true, 3, false) < ranking.key(true, 4, true));
+        assert!(ranking.key(true, 6, false) < ranking.key(false, 6, true));
+        assert_eq!(ranking.key(true, 3, true), ranking.key(true, 3, false));
+
+        let ranking = Ranking { tiebreaker: true };
+        assert!(ranking.key(true, 3, true) < ranking.key(true, 3, false));
+        assert!(ranking.key(true, 3, false) < ranking.key(true, 4, true));
+        assert!(ranking.key(true, 6, false) < ranking.key(false, 6, true));
+    }
+}
@@ -0,0 +1,163 @@
+use crate::mode::Mode;
+use crate::wordle::{PuzzleBoard, Theme, Tile};
+use crate::{find_puzzles, find_user_theme};
+use hank_pdk::{warn, Hank};
+use hank_types::message::Message;
+use std::collections::HashMap;
+
+/// Tile counts over a set of boards. Winning rows are left out since they're always all green.
+#[derive(Default)]
+struct BoardStats {
+    boards: usize,
+    rows: usize,
+    greens: usize,
+    yellows: usize,
+    blank_openers: usize,
+    conversions: Vec<usize>,
+    patterns: HashMap<Vec<Tile>, usize>,
+}
+
+impl BoardStats {
+    fn add(&mut self, board: &PuzzleBoard) {
+        let count = |row: &[Tile], tile: Tile| row.iter().filter(|t| **t == tile).count();
+
+        self.boards += 1;
+        if board
+            .board
+            .first()
+            .is_some_and(|row| row.iter().all(|tile| *tile == Tile::Black))
+        {
+            self.blank_openers += 1;
+        }
+
+        for row in board
+            .board
+            .iter()
+            .filter(|row| !row.iter().all(|tile| *tile == Tile::Green))
+        {
+            self.rows += 1;
+            self.greens += count(row, Tile::Green);
+            self.yellows += count(row, Tile::Yellow);
+            *self.patterns.entry(row.clone()).or_default() += 1;
+        }
+
+        // Yellows are converted once a later row has as many greens as the first row with a yellow
+        // had hits.
+        if let Some((first, row)) = board
+            .board
+            .iter()
+            .enumerate()
+            .find(|(_, row)| count(row, Tile::Yellow) > 0)
+        {
+            let hits = count(row, Tile::Green) + count(row, Tile::Yellow);
+            if let Some((converted, _)) = board
+                .board
+                .iter()
+                .enumerate()
+                .skip(first + 1)
+                .find(|(_, row)| count(row, Tile::Green) >= hits)
+            {
+                self.conversions.push(converted - first);
+            }
+        }
+    }
+
+    fn per_row(&self, count: usize) -> f64 {
+        count as f64 / self.rows.max(1) as f64
+    }
+
+    fn blank_opener_rate(&self) -> f64 {
+        self.blank_openers as f64 / self.boards.max(1) as f64 * 100.0
+    }
+
+    fn rows_to_convert(&self) -> f64 {
+        self.conversions.iter().sum::<usize>() as f64 / self.conversions.len().max(1) as f64
+    }
+
+    fn common_patterns(&self, theme: Theme) -> String {
+        let mut patterns = self.patterns.iter().collect::<Vec<_>>();
+        patterns.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+
+        patterns
+            .iter()
+            .take(3)
+            .map(|(row, count)| {
+                format!(
+                    "{} ×{}",
+                    row.iter()
+                        .map(|tile| tile.render(theme))
+                        .collect::<String>(),
+                    count
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("  ")
+    }
+}
+
+/// Compare the tiles on a player's boards with everyone else's.
+pub fn patterns(message: Message, user_id: Option<String>, mode: Mode) {
+    let Some(ref author) = message.author else {
+        return;
+    };
+    let user_id = user_id.unwrap_or_else(|| author.id.to_string());
+
+    let puzzles = match find_puzzles() {
+        Ok(puzzles) => puzzles,
+        Err(e) => {
+            warn!("couldn't look up puzzles: {}", e);
+            return;
+        }
+    };
+
+    let mut player = BoardStats::default();
+    let mut group = BoardStats::default();
+    let mut name = None;
+    for row in puzzles
+        .iter()
+        .filter(|row| mode.includes(row.puzzle.hard_mode))
+    {
+        group.add(&row.puzzle.board);
+        if row.submitted_by.to_string() == user_id {
+            player.add(&row.puzzle.board);
+            name = Some(&row.submitter);
+        }
+    }
+
+    let Some(name) = name else {
+        Hank::respond(
+            format!(
+                "<@{}> hasn't posted any Wordles{} yet.",
+                user_id,
+                mode.title()
+            ),
+            message,
+        );
+        return;
+    };
+
+    let theme = find_user_theme(&author.id.to_string());
+    let response = format!(
+        "**{}'s tile patterns{}** (everyone in brackets)\n\
+        Greens per row: {:.2} ({:.2})\n\
+        Yellows per row: {:.2} ({:.2})\n\
+        First rows with no hits: {:.0}% ({:.0}%)\n\
+        Rows to turn yellows green: {:.1} ({:.1})\n\
+        Most common rows: {}\n\
+        Everyone's most common rows: {}",
+        name,
+        mode.title(),
+        player.per_row(player.greens),
+        group.per_row(group.greens),
+        player.per_row(player.yellows),
+        group.per_row(group.yellows),
+        player.blank_opener_rate(),
+        group.blank_opener_rate(),
+        player.rows_to_convert(),
+        group.rows_to_convert(),
+        player.common_patterns(theme),
+        group.common_patterns(theme)
+    );
+
+    Hank::respond(response, message);
+}
@@ -0,0 +1,491 @@
+use crate::challenge;
+use crate::wordle::{Game, Puzzle, MAX_GUESSES};
+use crate::{
+    deserialize_bool, find_puzzle_by_user_and_day_offset, find_user_theme, get_current_puzzle,
+    insert_puzzle, is_direct_message, request_puzzle, wordle_launch_date,
+};
+use anyhow::{anyhow, Result};
+use hank_pdk::{warn, Hank};
+use hank_types::database::PreparedStatement;
+use hank_types::message::Message;
+use hank_types::user::User;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+pub struct GameRow {
+    pub id: u64,
+    pub player: String,
+    pub player_id: u64,
+    pub kind: String,
+    pub day_offset: u32,
+    pub solution: String,
+    #[serde(deserialize_with = "deserialize_bool")]
+    pub hard_mode: bool,
+    pub guesses: String,
+    pub started_at: chrono::DateTime<chrono::Local>,
+    pub finished_at: Option<chrono::DateTime<chrono::Local>>,
+}
+
+impl GameRow {
+    pub fn game(&self) -> Game {
+        Game {
+            solution: self.solution.clone(),
+            guesses: self.guesses.split_whitespace().map(String::from).collect(),
+            hard_mode: self.hard_mode,
+        }
+    }
+}
+
+pub fn install() {
+    let query = "
+CREATE TABLE IF NOT EXISTS game (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    player TEXT NOT NULL,
+    player_id INTEGER NOT NULL,
+    kind TEXT NOT NULL,
+    day_offset INTEGER NOT NULL,
+    solution TEXT NOT NULL,
+    hard_mode INTEGER NOT NULL,
+    guesses TEXT NOT NULL,
+    started_at TEXT NOT NULL,
+    finished_at TEXT
+);
+";
+    let _ = Hank::db_query(PreparedStatement::new(query).build());
+
+    // Archive results are kept apart from the puzzle table so they never count towards the
+    // daily leaderboards.
+    let query = "
+CREATE TABLE IF NOT EXISTS archive_puzzle (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    player TEXT NOT NULL,
+    player_id INTEGER NOT NULL,
+    played_at TEXT NOT NULL,
+    day_offset INTEGER NOT NULL,
+    attempts INTEGER NOT NULL,
+    solved INTEGER NOT NULL,
+    hard_mode INTEGER NOT NULL,
+    puzzle TEXT NOT NULL,
+    UNIQUE(player_id, day_offset)
+);
+";
+    let _ = Hank::db_query(PreparedStatement::new(query).build());
+}
+
+/// Start a private game of today's Wordle.
+pub fn start(message: Message, hard_mode: bool) {
+    let Some(ref user) = message.author else {
+        return;
+    };
+
+    let current = get_current_puzzle(false);
+    if current.solution.is_empty() {
+        Hank::respond(
+            "I couldn't fetch today's Wordle, try again in a bit.".into(),
+            message,
+        );
+        return;
+    }
+
+    if let Ok(Some(_)) = find_puzzle_by_user_and_day_offset(user, current.days_since_launch) {
+        Hank::respond("You've already posted today's Wordle!".into(), message);
+        return;
+    }
+
+    begin(
+        message,
+        "daily",
+        current.days_since_launch,
+        &format!("Wordle #{}", current.days_since_launch),
+        &current.solution,
+        hard_mode,
+    );
+}
+
+/// Start a private game of a past Wordle from the archive.
+pub fn start_archive(message: Message, day_offset: u32, hard_mode: bool) {
+    if day_offset >= get_current_puzzle(false).days_since_launch {
+        Hank::respond(
+            "The archive only has puzzles from before today.".into(),
+            message,
+        );
+        return;
+    }
+
+    let date = wordle_launch_date() + chrono::Duration::days(day_offset.into());
+    let solution = match request_puzzle(&date, 2) {
+        Ok(puzzle) => puzzle.solution,
+        Err(e) => {
+            warn!(
+                "couldn't fetch Wordle #{} for the archive: {}",
+                day_offset, e
+            );
+            Hank::respond(
+                format!(
+                    "I couldn't fetch Wordle #{}, try again in a bit.",
+                    day_offset
+                ),
+                message,
+            );
+            return;
+        }
+    };
+
+    begin(
+        message,
+        "archive",
+        day_offset,
+        &format!("Wordle #{}", day_offset),
+        &solution,
+        hard_mode,
+    );
+}
+
+/// Start a private game, `day_offset` identifies the puzzle within its kind of game and `title`
+/// is how it's referred to in responses.
+pub fn begin(
+    message: Message,
+    kind: &str,
+    day_offset: u32,
+    title: &str,
+    solution: &str,
+    hard_mode: bool,
+) {
+    let Some(ref user) = message.author else {
+        return;
+    };
+
+    if !is_direct_message(&message) {
+        Hank::respond(
+            "Send me a DM to play so you don't spoil the puzzle for everyone!".into(),
+            message,
+        );
+        return;
+    }
+
+    match find_game(user, kind, day_offset) {
+        Ok(Some(row)) if row.finished_at.is_none() => {
+            let game = row.game();
+            let theme = find_user_theme(&user.id.to_string());
+            Hank::respond(
+                format!(
+                    "You already have a game of {} in progress!\n\n{}\n\n{}",
+                    title,
+                    game.render(theme),
+                    game.keyboard()
+                ),
+                message,
+            );
+            return;
+        }
+        Ok(Some(_)) => {
+            Hank::respond(format!("You've already played {}!", title), message);
+            return;
+        }
+        Ok(None) => (),
+        Err(e) => {
+            warn!("couldn't look up {}'s games: {}", user.name, e);
+            return;
+        }
+    }
+
+    if !can_start_game(&message, user) {
+        return;
+    }
+
+    if let Err(e) = insert_game(user, kind, day_offset, solution, hard_mode) {
+        warn!("couldn't start a game for {}: {}", user.name, e);
+        return;
+    }
+
+    Hank::respond(
+        format!(
+            "{}{} started! You have {} guesses, make one with `wordle guess WORD`.",
+            title,
+            if hard_mode { " (hard mode)" } else { "" },
+            MAX_GUESSES
+        ),
+        message,
+    );
+}
+
+/// Submit a guess to the user's game in progress.
+pub fn guess(message: Message, guess: &str) {
+    let Some(ref user) = message.author else {
+        return;
+    };
+
+    if !is_direct_message(&message) {
+        Hank::respond(
+            "Send me your guesses in a DM so you don't spoil the puzzle for everyone!".into(),
+            message,
+        );
+        return;
+    }
+
+    let row = match find_active_game(user) {
+        Ok(Some(row)) => row,
+        Ok(None) => {
+            Hank::respond(
+                "You don't have a game in progress, start one with `wordle play`.".into(),
+                message,
+            );
+            return;
+        }
+        Err(e) => {
+            warn!("couldn't look up {}'s active game: {}", user.name, e);
+            return;
+        }
+    };
+
+    if row.kind == "daily" && row.day_offset != get_current_puzzle(false).days_since_launch {
+        let _ = finish_game(&row);
+        Hank::respond(
+            format!(
+                "Your game of Wordle #{} has expired, start a new one with `wordle play`.",
+                row.day_offset
+            ),
+            message,
+        );
+        return;
+    }
+
+    let mut game = row.game();
+    if let Err(e) = game.guess(guess) {
+        Hank::respond(format!("Invalid guess: {}", e), message);
+        return;
+    }
+
+    if let Err(e) = update_game(&row, &game) {
+        warn!("couldn't save {}'s guess: {}", user.name, e);
+        return;
+    }
+
+    let theme = find_user_theme(&user.id.to_string());
+    let mut response = format!("{}\n\n{}", game.render(theme), game.keyboard());
+
+    if game.is_finished() {
+        let puzzle = game.share(row.day_offset, theme);
+        let footer = match row.kind.as_str() {
+            // A finished game of the daily puzzle counts the same as posting the result.
+            "daily" => {
+                if let Err(e) = insert_puzzle(user, &puzzle) {
+                    warn!("couldn't record {}'s result: {:?}", user.name, e);
+                }
+                String::try_from(puzzle).unwrap_or_default()
+            }
+            "archive" => {
+                if let Err(e) = insert_archive_puzzle(user, &puzzle) {
+                    warn!("couldn't record {}'s archive result: {}", user.name, e);
+                }
+                String::try_from(puzzle).unwrap_or_default()
+            }
+            // Challenge results are announced in the channel once both players are done.
+            "challenge" => {
+                challenge::game_finished(&row);
+                "I'll announce the result once your opponent has finished.".into()
+            }
+            // Custom puzzles aren't numbered, so there's nothing to share.
+            _ => String::new(),
+        };
+
+        response = format!(
+            "{}\n\n{}\n\n{}",
+            if game.is_solved() {
+                "You got it!".to_string()
+            } else {
+                format!("Better luck next time, the word was **{}**.", game.solution)
+            },
+            game.render(theme),
+            footer
+        )
+        .trim_end()
+        .to_string();
+    } else {
+        response.push_str(&format!(
+            "\n\n{}/{} guesses used",
+            game.guesses.len(),
+            MAX_GUESSES
+        ));
+    }
+
+    Hank::respond(response, message);
+}
+
+/// Guesses go to the game in progress, so only one game can be played at a time. Tells the user
+/// to finish the game they're playing first if there is one.
+pub fn can_start_game(message: &Message, user: &User) -> bool {
+    let row = match find_active_game(user) {
+        Ok(Some(row)) => row,
+        Ok(None) => return true,
+        Err(e) => {
+            warn!("couldn't look up {}'s active game: {}", user.name, e);
+            return false;
+        }
+    };
+
+    // A game of a past day's Wordle can't be finished any more, so it doesn't get in the way.
+    if row.kind == "daily" && row.day_offset != get_current_puzzle(false).days_since_launch {
+        if let Err(e) = finish_game(&row) {
+            warn!("couldn't expire {}'s game: {}", user.name, e);
+            return false;
+        }
+        return true;
+    }
+
+    let title = match row.kind.as_str() {
+        "daily" | "archive" => format!("Wordle #{}", row.day_offset),
+        "challenge" => "your challenge".into(),
+        _ => "your custom puzzle".into(),
+    };
+    Hank::respond(
+        format!(
+            "Finish your game of {} first, your guesses go to it until it's over.",
+            title
+        ),
+        message.clone(),
+    );
+
+    false
+}
+
+pub fn insert_game(
+    user: &User,
+    kind: &str,
+    day_offset: u32,
+    solution: &str,
+    hard_mode: bool,
+) -> Result<()> {
+    let query = "
+INSERT INTO game (player, player_id, kind, day_offset, solution, hard_mode, guesses, started_at)
+VALUES (?, ?, ?, ?, ?, ?, '', ?)
+";
+    let statement = PreparedStatement::new(query)
+        .values([
+            user.name.clone(),
+            user.id.to_string(),
+            kind.to_string(),
+            day_offset.to_string(),
+            solution.to_ascii_lowercase(),
+            hard_mode.to_string(),
+            Hank::datetime().to_rfc3339(),
+        ])
+        .build();
+
+    Hank::db_query(statement)
+        .map(|_| ())
+        .map_err(|e| anyhow!(e))
+}
+
+fn insert_archive_puzzle(user: &User, puzzle: &Puzzle) -> Result<()> {
+    let query = "
+INSERT INTO archive_puzzle (player, player_id, played_at, day_offset, attempts, solved, hard_mode, puzzle)
+VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+";
+    let statement = PreparedStatement::new(query)
+        .values([
+            user.name.clone(),
+            user.id.to_string(),
+            Hank::datetime().to_rfc3339(),
+            puzzle.day_offset.to_string(),
+            puzzle.attempts.to_string(),
+            puzzle.solved.to_string(),
+            puzzle.hard_mode.to_string(),
+            puzzle.clone().try_into()?,
+        ])
+        .build();
+
+    Hank::db_query(statement)
+        .map(|_| ())
+        .map_err(|e| anyhow!(e))
+}
+
+fn update_game(row: &GameRow, game: &Game) -> Result<()> {
+    let finished_at = if game.is_finished() {
+        Hank::datetime().to_rfc3339()
+    } else {
+        String::new()
+    };
+    let statement = PreparedStatement::new(
+        "UPDATE game SET guesses = ?, finished_at = NULLIF(?, '') WHERE id = ?",
+    )
+    .values([game.guesses.join(" "), finished_at, row.id.to_string()])
+    .build();
+
+    Hank::db_query(statement)
+        .map(|_| ())
+        .map_err(|e| anyhow!(e))
+}
+
+fn finish_game(row: &GameRow) -> Result<()> {
+    let statement = PreparedStatement::new("UPDATE game SET finished_at = ? WHERE id = ?")
+        .values([Hank::datetime().to_rfc3339(), row.id.to_string()])
+        .build();
+
+    Hank::db_query(statement)
+        .map(|_| ())
+        .map_err(|e| anyhow!(e))
+}
+
+fn find_game(user: &User, kind: &str, day_offset: u32) -> Result<Option<GameRow>> {
+    let query = "
+SELECT * FROM game
+WHERE player_id = ? AND kind = ? AND day_offset = ?
+ORDER BY started_at DESC
+LIMIT 1
+";
+    let statement = PreparedStatement::new(query)
+        .values([
+            user.id.to_string(),
+            kind.to_string(),
+            day_offset.to_string(),
+        ])
+        .build();
+
+    Ok(Hank::db_fetch::<GameRow>(statement)
+        .map_err(|e| anyhow!(e))?
+        .into_iter()
+        .next())
+}
+
+pub fn find_active_game(user: &User) -> Result<Option<GameRow>> {
+    let query = "
+SELECT * FROM game
+WHERE player_id = ? AND finished_at IS NULL
+ORDER BY started_at DESC
+LIMIT 1
+";
+    let statement = PreparedStatement::new(query)
+        .values([user.id.to_string()])
+        .build();
+
+    Ok(Hank::db_fetch::<GameRow>(statement)
+        .map_err(|e| anyhow!(e))?
+        .into_iter()
+        .next())
+}
+
+pub fn find_game_by_id(id: u64) -> Result<Option<GameRow>> {
+    let statement = PreparedStatement::new("SELECT * FROM game WHERE id = ?")
+        .values([id.to_string()])
+        .build();
+
+    Ok(Hank::db_fetch::<GameRow>(statement)
+        .map_err(|e| anyhow!(e))?
+        .into_iter()
+        .next())
+}
+
+pub fn find_finished_games(kind: &str, day_offset: u32) -> Result<Vec<GameRow>> {
+    let query = "
+SELECT * FROM game
+WHERE kind = ? AND day_offset = ? AND finished_at IS NOT NULL
+ORDER BY finished_at ASC
+";
+    let statement = PreparedStatement::new(query)
+        .values([kind.to_string(), day_offset.to_string()])
+        .build();
+
+    Hank::db_fetch::<GameRow>(statement).map_err(|e| anyhow!(e))
+}
@@ -0,0 +1,294 @@
+use crate::mode::{Mode, Ranking};
+use crate::{check_admin, season, wordle_launch_date};
+use anyhow::{anyhow, Result};
+use chrono::Datelike;
+use hank_pdk::{warn, Hank};
+use hank_types::database::PreparedStatement;
+use hank_types::message::Message;
+use serde::Deserialize;
+
+/// The weights that make up the scoring formula, with their defaults: 7 minus attempts for a
+/// solve, nothing for an X, a point for hard mode and a point for the best result of the day.
+const WEIGHTS: [(&str, f64, &str); 6] = [
+    (
+        "solve",
+        7.0,
+        "points for solving, before attempts are taken away",
+    ),
+    ("attempt", 1.0, "points taken away for each attempt"),
+    ("fail", 0.0, "points for failing"),
+    ("hard_mode", 1.0, "bonus for solving in hard mode"),
+    ("daily_winner", 1.0, "bonus for the best result of the day"),
+    ("missed_day", 0.0, "points taken away for each missed day"),
+];
+
+#[derive(Debug, Deserialize)]
+struct WeightRow {
+    name: String,
+    value: f64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Standing {
+    pub player_id: u64,
+    pub player: String,
+    pub days: u32,
+    pub hard_mode_rate: f64,
+    pub points: f64,
+}
+
+pub fn install() {
+    let query = "
+CREATE TABLE IF NOT EXISTS point_weight (
+    name TEXT NOT NULL PRIMARY KEY,
+    value REAL NOT NULL
+);
+";
+    let _ = Hank::db_query(PreparedStatement::new(query).build());
+
+    for (name, value, _) in WEIGHTS {
+        let statement = PreparedStatement::new(
+            "INSERT INTO point_weight (name, value) VALUES (?, ?) ON CONFLICT(name) DO NOTHING",
+        )
+        .values([name.to_string(), value.to_string()])
+        .build();
+        let _ = Hank::db_query(statement);
+    }
+
+    // Points are worked out per result ahead of time and recomputed whenever the weights change.
+    let query = "
+CREATE TABLE IF NOT EXISTS puzzle_points (
+    puzzle_id INTEGER NOT NULL PRIMARY KEY,
+    player_id INTEGER NOT NULL,
+    player TEXT NOT NULL,
+    day_offset INTEGER NOT NULL,
+    submitted_date TEXT NOT NULL,
+    points REAL NOT NULL
+);
+";
+    let _ = Hank::db_query(PreparedStatement::new(query).build());
+
+    if let Err(e) = recompute(0, u32::MAX) {
+        warn!("couldn't compute points: {}", e);
+    }
+}
+
+/// Show the scoring formula.
+pub fn scoring(message: Message) {
+    let weights = match find_weights() {
+        Ok(weights) => weights,
+        Err(e) => {
+            warn!("couldn't look up point weights: {}", e);
+            return;
+        }
+    };
+
+    let mut response = String::from("**Scoring**\n");
+    for (name, _, description) in WEIGHTS {
+        let value = weights
+            .iter()
+            .find(|weight| weight.name == name)
+            .map(|weight| weight.value)
+            .unwrap_or_default();
+        response.push_str(&format!("`{}` {} - {}\n", name, value, description));
+    }
+    response.push_str("\nChange one with `wordle scoring NAME VALUE`.");
+
+    Hank::respond(response, message);
+}
+
+/// Change one of the scoring weights and recompute everyone's points.
+pub fn set_weight(message: Message, name: &str, value: &str) {
+    if !check_admin(&message) {
+        return;
+    }
+
+    if !WEIGHTS.iter().any(|(weight, _, _)| *weight == name) {
+        Hank::respond(
+            format!(
+                "{} isn't a scoring weight, pick one of {}",
+                name,
+                WEIGHTS.map(|(weight, _, _)| weight).join(", ")
+            ),
+            message,
+        );
+        return;
+    }
+    let Ok(value) = value.parse::<f64>() else {
+        Hank::respond(format!("{} isn't a number", value), message);
+        return;
+    };
+
+    let statement = PreparedStatement::new("UPDATE point_weight SET value = ? WHERE name = ?")
+        .values([value.to_string(), name.to_string()])
+        .build();
+    if let Err(e) = Hank::db_query(statement)
+        .map_err(|e| anyhow!(e))
+        .and_then(|_| recompute(0, u32::MAX))
+    {
+        warn!("couldn't update the {} weight: {}", name, e);
+        return;
+    }
+
+    Hank::respond(
+        format!(
+            "`{}` is now worth {}, points have been recalculated.",
+            name, value
+        ),
+        message,
+    );
+}
+
+/// Show who has the most points this season, week, month or of all time.
+pub fn leaderboard(message: Message, period: &str, mode: Mode) {
+    let today = Hank::datetime().date_naive();
+    let (title, start) = match period {
+        "season" => ("This Season", season::current_start()),
+        "week" => (
+            "This Week",
+            today - chrono::Duration::days(today.weekday().num_days_from_monday().into()),
+        ),
+        "month" => ("This Month", today.with_day(1).unwrap_or(today)),
+        "all" => ("All Time", wordle_launch_date()),
+        _ => {
+            Hank::respond(
+                "Usage: `wordle points [season|week|month|all] [hard|normal]`".into(),
+                message,
+            );
+            return;
+        }
+    };
+
+    let standings = match standings(start, today, mode) {
+        Ok(standings) => standings,
+        Err(e) => {
+            warn!("couldn't look up points: {}", e);
+            return;
+        }
+    };
+
+    if standings.is_empty() {
+        Hank::respond("Nobody has any points yet.".into(), message);
+        return;
+    }
+
+    Hank::respond(
+        format!(
+            "**Points {}{}**\n{}",
+            title,
+            mode.title(),
+            render_standings(&standings, 10)
+        ),
+        message,
+    );
+}
+
+fn render_standings(standings: &[Standing], limit: usize) -> String {
+    standings
+        .iter()
+        .take(limit)
+        .enumerate()
+        .map(|(i, standing)| {
+            format!(
+                "{}. {} - {} over {}, {:.0}% in hard mode",
+                i + 1,
+                standing.player,
+                pluralizer::pluralize("point", standing.points.round() as isize, true),
+                pluralizer::pluralize("day", standing.days as isize, true),
+                standing.hard_mode_rate * 100.0
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Everyone's points for results in the mode submitted between the dates, best first. Players
+/// lose points for each day they missed since they first played in the period, not counting today
+/// since there's still time to play. A day played in the other mode isn't missed, it just doesn't
+/// earn anything.
+pub fn standings(
+    start: chrono::NaiveDate,
+    end: chrono::NaiveDate,
+    mode: Mode,
+) -> Result<Vec<Standing>> {
+    let penalty_end = end.min(Hank::datetime().date_naive() - chrono::Duration::days(1));
+    let query = format!(
+        "
+SELECT
+    pp.player_id,
+    MAX(pp.player) AS player,
+    SUM(CASE WHEN {mode} THEN 1 ELSE 0 END) AS days,
+    AVG(CASE WHEN p.hard_mode = 'true' THEN 1.0 ELSE 0.0 END) AS hard_mode_rate,
+    SUM(CASE WHEN {mode} THEN pp.points ELSE 0 END) - MAX(
+        0,
+        JULIANDAY(?) - JULIANDAY(MIN(pp.submitted_date)) + 1
+            - SUM(CASE WHEN pp.submitted_date <= ? THEN 1 ELSE 0 END)
+    ) * (SELECT value FROM point_weight WHERE name = 'missed_day') AS points
+FROM puzzle_points pp
+JOIN puzzle p ON p.id = pp.puzzle_id
+WHERE pp.submitted_date BETWEEN ? AND ?
+GROUP BY pp.player_id
+HAVING days > 0
+ORDER BY points DESC
+",
+        mode = mode.condition("p.hard_mode")
+    );
+    let statement = PreparedStatement::new(query)
+        .values([
+            penalty_end.to_string(),
+            penalty_end.to_string(),
+            start.to_string(),
+            end.to_string(),
+        ])
+        .build();
+
+    Hank::db_fetch::<Standing>(statement).map_err(|e| anyhow!(e))
+}
+
+/// Work out the points for every result of the puzzles between the day offsets.
+pub fn recompute(from: u32, to: u32) -> Result<()> {
+    let query = format!(
+        "
+INSERT INTO puzzle_points (puzzle_id, player_id, player, day_offset, submitted_date, points)
+SELECT
+    p.id,
+    p.submitted_by,
+    p.submitter,
+    p.day_offset,
+    p.submitted_date,
+    CASE WHEN p.solved = 'true' THEN w.solve - w.attempt * p.attempts ELSE w.fail END
+    + CASE WHEN p.solved = 'true' AND p.hard_mode = 'true' THEN w.hard_mode ELSE 0 END
+    + CASE
+        WHEN p.solved = 'true' AND NOT EXISTS (
+            SELECT 1 FROM puzzle d WHERE d.day_offset = p.day_offset AND d.solved = 'true' AND {}
+        ) THEN w.daily_winner
+        ELSE 0
+    END
+FROM puzzle p, (
+    SELECT
+        MAX(CASE WHEN name = 'solve' THEN value END) AS solve,
+        MAX(CASE WHEN name = 'attempt' THEN value END) AS attempt,
+        MAX(CASE WHEN name = 'fail' THEN value END) AS fail,
+        MAX(CASE WHEN name = 'hard_mode' THEN value END) AS hard_mode,
+        MAX(CASE WHEN name = 'daily_winner' THEN value END) AS daily_winner
+    FROM point_weight
+) w
+WHERE p.day_offset BETWEEN ? AND ?
+ON CONFLICT(puzzle_id) DO UPDATE SET points = excluded.points
+",
+        Ranking::current().outranks("d", "p")
+    );
+    let statement = PreparedStatement::new(query)
+        .values([from.to_string(), to.to_string()])
+        .build();
+
+    Hank::db_query(statement)
+        .map(|_| ())
+        .map_err(|e| anyhow!(e))
+}
+
+fn find_weights() -> Result<Vec<WeightRow>> {
+    let statement = PreparedStatement::new("SELECT * FROM point_weight").build();
+
+    Hank::db_fetch::<WeightRow>(statement).map_err(|e| anyhow!(e))
+}
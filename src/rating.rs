@@ -0,0 +1,372 @@
+use crate::mode::{Mode, Ranking};
+use crate::{deserialize_bool, get_current_puzzle};
+use anyhow::{anyhow, Result};
+use hank_pdk::{info, warn, Hank};
+use hank_types::database::PreparedStatement;
+use hank_types::message::Message;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+const INITIAL_RATING: f64 = 1500.0;
+const K_FACTOR: f64 = 32.0;
+
+#[derive(Debug, Deserialize)]
+struct RatingRow {
+    player_id: u64,
+    player: String,
+    rating: f64,
+    games: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct RatingHistoryRow {
+    player_id: u64,
+    day_offset: u32,
+    rating_before: f64,
+    rating_after: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ResultRow {
+    submitter: String,
+    submitted_by: u64,
+    attempts: u32,
+    #[serde(deserialize_with = "deserialize_bool")]
+    solved: bool,
+    #[serde(deserialize_with = "deserialize_bool")]
+    hard_mode: bool,
+}
+
+impl ResultRow {
+    fn key(&self, ranking: Ranking) -> (u32, bool) {
+        ranking.key(self.solved, self.attempts, self.hard_mode)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct DayRow {
+    day_offset: u32,
+}
+
+pub fn install() {
+    let query = "
+CREATE TABLE IF NOT EXISTS rating (
+    player_id INTEGER NOT NULL,
+    mode TEXT NOT NULL,
+    player TEXT NOT NULL,
+    rating REAL NOT NULL,
+    games INTEGER NOT NULL,
+    updated_at TEXT NOT NULL,
+    PRIMARY KEY(player_id, mode)
+);
+";
+    let _ = Hank::db_query(PreparedStatement::new(query).build());
+
+    let query = "
+CREATE TABLE IF NOT EXISTS rating_history (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    player_id INTEGER NOT NULL,
+    day_offset INTEGER NOT NULL,
+    mode TEXT NOT NULL,
+    rating_before REAL NOT NULL,
+    rating_after REAL NOT NULL,
+    rated_at TEXT NOT NULL,
+    UNIQUE(player_id, day_offset, mode)
+);
+";
+    let _ = Hank::db_query(PreparedStatement::new(query).build());
+
+    // Days are marked as rated even when too few people played them to change any ratings, so
+    // they aren't looked at again.
+    let query = "
+CREATE TABLE IF NOT EXISTS rated_day (
+    day_offset INTEGER NOT NULL PRIMARY KEY,
+    rated_at TEXT NOT NULL
+);
+";
+    let _ = Hank::db_query(PreparedStatement::new(query).build());
+}
+
+/// Rate every day before today that hasn't been rated yet, oldest first.
+pub fn close_days() {
+    let days = match find_unrated_days(get_current_puzzle(false).days_since_launch) {
+        Ok(days) => days,
+        Err(e) => {
+            warn!("couldn't look up unrated days: {}", e);
+            return;
+        }
+    };
+
+    for day in days {
+        info!("Rating Wordle #{}...", day.day_offset);
+        if let Err(e) = rate_day(day.day_offset) {
+            warn!("couldn't rate Wordle #{}: {}", day.day_offset, e);
+            return;
+        }
+    }
+}
+
+/// Show the user's rating in the mode and how it's changed recently.
+pub fn rating(message: Message, mode: Mode) {
+    let Some(ref user) = message.author else {
+        return;
+    };
+
+    let statement = PreparedStatement::new("SELECT * FROM rating WHERE player_id = ? AND mode = ?")
+        .values([user.id.to_string(), mode.name().to_string()])
+        .build();
+    let Some(rating) = Hank::db_fetch::<RatingRow>(statement)
+        .ok()
+        .and_then(|rows| rows.into_iter().next())
+    else {
+        Hank::respond(
+            format!(
+                "You don't have a rating{} yet, it's updated the day after you post a result alongside someone else.",
+                mode.title()
+            ),
+            message,
+        );
+        return;
+    };
+
+    let statement = PreparedStatement::new(
+        "SELECT * FROM rating_history WHERE player_id = ? AND mode = ? ORDER BY day_offset DESC LIMIT 5",
+    )
+    .values([user.id.to_string(), mode.name().to_string()])
+    .build();
+    let history = Hank::db_fetch::<RatingHistoryRow>(statement)
+        .unwrap_or_default()
+        .iter()
+        .map(|row| {
+            format!(
+                "Wordle #{}: {:.0} ({:+.0})",
+                row.day_offset,
+                row.rating_after,
+                row.rating_after - row.rating_before
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    Hank::respond(
+        format!(
+            "**{}** is rated **{:.0}**{} after {}\n{}",
+            rating.player,
+            rating.rating,
+            mode.title(),
+            pluralizer::pluralize("rated day", rating.games as isize, true),
+            history
+        ),
+        message,
+    );
+}
+
+/// The highest rated players in the mode.
+pub fn leaderboard(message: Message, mode: Mode) {
+    let statement =
+        PreparedStatement::new("SELECT * FROM rating WHERE mode = ? ORDER BY rating DESC LIMIT 10")
+            .values([mode.name().to_string()])
+            .build();
+    let ratings = match Hank::db_fetch::<RatingRow>(statement) {
+        Ok(ratings) => ratings,
+        Err(e) => {
+            warn!("couldn't look up ratings: {}", e);
+            return;
+        }
+    };
+
+    if ratings.is_empty() {
+        Hank::respond(format!("Nobody has a rating{} yet.", mode.title()), message);
+        return;
+    }
+
+    let mut response = format!("**Top Rated Wordlers{}**\n", mode.title());
+    for (i, rating) in ratings.iter().enumerate() {
+        response.push_str(&format!(
+            "{}. {} - {:.0} ({})\n",
+            i + 1,
+            rating.player,
+            rating.rating,
+            pluralizer::pluralize("day", rating.games as isize, true)
+        ));
+    }
+
+    Hank::respond(response, message);
+}
+
+/// Rate the day's results, once across every result and again for each mode on its own.
+fn rate_day(day_offset: u32) -> Result<()> {
+    let results = find_results(day_offset)?;
+    for mode in Mode::EVERY {
+        let results = results
+            .iter()
+            .filter(|result| mode.includes(result.hard_mode))
+            .collect::<Vec<_>>();
+        rate_results(day_offset, mode, &results)?;
+    }
+
+    let statement = PreparedStatement::new(
+        "INSERT INTO rated_day (day_offset, rated_at) VALUES (?, ?) ON CONFLICT(day_offset) DO NOTHING",
+    )
+    .values([day_offset.to_string(), Hank::datetime().to_rfc3339()])
+    .build();
+    Hank::db_query(statement)
+        .map(|_| ())
+        .map_err(|e| anyhow!(e))
+}
+
+/// Treat the results as a match between everyone that played: each player is compared with every
+/// other player as if it was a game of Elo, with the rating change averaged out so a busy day
+/// doesn't swing ratings more than a quiet one.
+///
+/// Each player's change is recorded in their history before their rating is updated from it, so
+/// if rating the day fails part way through, trying again skips the players already rated.
+fn rate_results(day_offset: u32, mode: Mode, results: &[&ResultRow]) -> Result<()> {
+    if results.len() < 2 {
+        return Ok(());
+    }
+
+    let rated = find_ratings_before(day_offset, mode)?;
+    let ratings = find_ratings(mode)?;
+    let current = |player_id: u64| {
+        rated
+            .get(&player_id)
+            .copied()
+            .or_else(|| ratings.get(&player_id).map(|row| row.rating))
+            .unwrap_or(INITIAL_RATING)
+    };
+
+    let ranking = Ranking::current();
+    let opponents = (results.len() - 1) as f64;
+    for player in results {
+        if rated.contains_key(&player.submitted_by) {
+            continue;
+        }
+
+        let before = current(player.submitted_by);
+        let change = results
+            .iter()
+            .filter(|opponent| opponent.submitted_by != player.submitted_by)
+            .map(|opponent| {
+                let expected =
+                    1.0 / (1.0 + 10f64.powf((current(opponent.submitted_by) - before) / 400.0));
+                let actual = match player.key(ranking).cmp(&opponent.key(ranking)) {
+                    std::cmp::Ordering::Less => 1.0,
+                    std::cmp::Ordering::Equal => 0.5,
+                    std::cmp::Ordering::Greater => 0.0,
+                };
+                actual - expected
+            })
+            .sum::<f64>()
+            * K_FACTOR
+            / opponents;
+
+        insert_rating_history(player, day_offset, mode, before, before + change)?;
+    }
+
+    for player in results {
+        update_rating(player, mode)?;
+    }
+
+    Ok(())
+}
+
+fn insert_rating_history(
+    player: &ResultRow,
+    day_offset: u32,
+    mode: Mode,
+    before: f64,
+    after: f64,
+) -> Result<()> {
+    let query = "
+INSERT INTO rating_history (player_id, day_offset, mode, rating_before, rating_after, rated_at)
+VALUES (?, ?, ?, ?, ?, ?)
+";
+    let statement = PreparedStatement::new(query)
+        .values([
+            player.submitted_by.to_string(),
+            day_offset.to_string(),
+            mode.name().to_string(),
+            before.to_string(),
+            after.to_string(),
+            Hank::datetime().to_rfc3339(),
+        ])
+        .build();
+    Hank::db_query(statement)
+        .map(|_| ())
+        .map_err(|e| anyhow!(e))
+}
+
+/// Set the player's rating in the mode from their latest rated day.
+fn update_rating(player: &ResultRow, mode: Mode) -> Result<()> {
+    let query = "
+INSERT INTO rating (player_id, mode, player, rating, games, updated_at)
+SELECT player_id, mode, ?, rating_after, COUNT(*) OVER (), ?
+FROM rating_history
+WHERE player_id = ? AND mode = ?
+ORDER BY day_offset DESC
+LIMIT 1
+ON CONFLICT(player_id, mode) DO UPDATE
+SET player = excluded.player, rating = excluded.rating, games = excluded.games, updated_at = excluded.updated_at
+";
+    let statement = PreparedStatement::new(query)
+        .values([
+            player.submitter.clone(),
+            Hank::datetime().to_rfc3339(),
+            player.submitted_by.to_string(),
+            mode.name().to_string(),
+        ])
+        .build();
+    Hank::db_query(statement)
+        .map(|_| ())
+        .map_err(|e| anyhow!(e))
+}
+
+/// The ratings players that were already rated for the day in the mode went into it with.
+fn find_ratings_before(day_offset: u32, mode: Mode) -> Result<HashMap<u64, f64>> {
+    let statement =
+        PreparedStatement::new("SELECT * FROM rating_history WHERE day_offset = ? AND mode = ?")
+            .values([day_offset.to_string(), mode.name().to_string()])
+            .build();
+
+    Ok(Hank::db_fetch::<RatingHistoryRow>(statement)
+        .map_err(|e| anyhow!(e))?
+        .into_iter()
+        .map(|row| (row.player_id, row.rating_before))
+        .collect())
+}
+
+fn find_ratings(mode: Mode) -> Result<HashMap<u64, RatingRow>> {
+    let statement = PreparedStatement::new("SELECT * FROM rating WHERE mode = ?")
+        .values([mode.name().to_string()])
+        .build();
+
+    Ok(Hank::db_fetch::<RatingRow>(statement)
+        .map_err(|e| anyhow!(e))?
+        .into_iter()
+        .map(|row| (row.player_id, row))
+        .collect())
+}
+
+fn find_results(day_offset: u32) -> Result<Vec<ResultRow>> {
+    let statement = PreparedStatement::new(
+        "SELECT submitter, submitted_by, attempts, solved, hard_mode FROM puzzle WHERE day_offset = ?",
+    )
+    .values([day_offset.to_string()])
+    .build();
+
+    Hank::db_fetch::<ResultRow>(statement).map_err(|e| anyhow!(e))
+}
+
+fn find_unrated_days(today: u32) -> Result<Vec<DayRow>> {
+    let query = "
+SELECT DISTINCT day_offset FROM puzzle
+WHERE day_offset < ? AND day_offset NOT IN (SELECT day_offset FROM rated_day)
+ORDER BY day_offset ASC
+";
+    let statement = PreparedStatement::new(query)
+        .values([today.to_string()])
+        .build();
+
+    Hank::db_fetch::<DayRow>(statement).map_err(|e| anyhow!(e))
+}
@@ -0,0 +1,272 @@
+use crate::mode::{Mode, Ranking};
+use crate::wrapped::daily_wins;
+use crate::{
+    announce, check_admin, difficulty, find_puzzles_between, find_setting, set_setting, PuzzleRow,
+};
+use anyhow::Result;
+use chrono::{Datelike, Months};
+use hank_pdk::{warn, Hank};
+use hank_types::message::Message;
+use oxford_join::OxfordJoin;
+use std::collections::{HashMap, HashSet};
+
+/// The recaps that can be posted, each turned on or off with its own setting.
+const RECAPS: [&str; 2] = ["weekly", "monthly"];
+
+struct PlayerSummary<'a> {
+    name: &'a str,
+    days: HashSet<u32>,
+    scores: Vec<u32>,
+    wins: usize,
+}
+
+impl PlayerSummary<'_> {
+    fn average(&self) -> f64 {
+        self.scores.iter().sum::<u32>() as f64 / self.scores.len() as f64
+    }
+}
+
+/// Post last week's recap, run on Mondays.
+pub fn announce_weekly() {
+    announce_recap("weekly");
+}
+
+/// Post last month's recap, run on the first of the month.
+pub fn announce_monthly() {
+    announce_recap("monthly");
+}
+
+/// Show the most recent recap of results in the mode without waiting for it to be posted.
+pub fn recap(message: Message, kind: &str, mode: Mode) {
+    if !RECAPS.contains(&kind) {
+        Hank::respond(
+            format!(
+                "Usage: `wordle recap [{}] [on|off|hard|normal]`",
+                RECAPS.join("|")
+            ),
+            message,
+        );
+        return;
+    }
+
+    let response = match build_recap(kind, mode) {
+        Ok(Some(recap)) => recap,
+        Ok(None) => "Nobody played, so there's nothing to recap.".into(),
+        Err(e) => {
+            warn!("couldn't build the {} recap: {}", kind, e);
+            return;
+        }
+    };
+
+    Hank::respond(response, message);
+}
+
+/// Turn a recap on or off.
+pub fn set_enabled(message: Message, kind: &str, enabled: bool) {
+    if !check_admin(&message) {
+        return;
+    }
+
+    if !RECAPS.contains(&kind) {
+        Hank::respond(
+            format!("Usage: `wordle recap [{}] [on|off]`", RECAPS.join("|")),
+            message,
+        );
+        return;
+    }
+
+    if let Err(e) = set_setting(&format!("{}_recap", kind), &enabled.to_string()) {
+        warn!("couldn't change the {} recap: {}", kind, e);
+        return;
+    }
+
+    Hank::respond(
+        format!(
+            "The {} recap is now {}.",
+            kind,
+            if enabled { "on" } else { "off" }
+        ),
+        message,
+    );
+}
+
+fn announce_recap(kind: &str) {
+    // Recaps are on unless they've been turned off.
+    if find_setting(&format!("{}_recap", kind))
+        .ok()
+        .flatten()
+        .is_some_and(|enabled| enabled == "false")
+    {
+        return;
+    }
+
+    match build_recap(kind, Mode::All) {
+        Ok(Some(recap)) => announce(recap),
+        Ok(None) => {}
+        Err(e) => warn!("couldn't build the {} recap: {}", kind, e),
+    }
+}
+
+/// The recap of results in the mode over the last full week or month, compared with the one before
+/// it.
+fn build_recap(kind: &str, mode: Mode) -> Result<Option<String>> {
+    let today = Hank::datetime().date_naive();
+    let (title, start, previous_start) = match kind {
+        "weekly" => {
+            let this_week =
+                today - chrono::Duration::days(today.weekday().num_days_from_monday().into());
+            let start = this_week - chrono::Duration::days(7);
+            (
+                format!("Week of {}", start),
+                start,
+                start - chrono::Duration::days(7),
+            )
+        }
+        _ => {
+            let this_month = today.with_day(1).unwrap_or(today);
+            let start = this_month - Months::new(1);
+            (
+                start.format("%B %Y").to_string(),
+                start,
+                start - Months::new(1),
+            )
+        }
+    };
+    let end = match kind {
+        "weekly" => start + chrono::Duration::days(6),
+        _ => start + Months::new(1) - chrono::Duration::days(1),
+    };
+
+    let in_mode = |puzzles: Vec<PuzzleRow>| {
+        puzzles
+            .into_iter()
+            .filter(|row| mode.includes(row.puzzle.hard_mode))
+            .collect::<Vec<_>>()
+    };
+    let puzzles = in_mode(find_puzzles_between(start, end)?);
+    if puzzles.is_empty() {
+        return Ok(None);
+    }
+    let previous = in_mode(find_puzzles_between(
+        previous_start,
+        start - chrono::Duration::days(1),
+    )?);
+
+    let players = summarize(&puzzles);
+    let previous_players = summarize(&previous);
+    let days = (end - start).num_days() as usize + 1;
+
+    let mut recap = format!(
+        "**Wordle Recap: {}{}**\n{} posted {}",
+        title,
+        mode.title(),
+        pluralizer::pluralize("player", players.len() as isize, true),
+        pluralizer::pluralize("result", puzzles.len() as isize, true)
+    );
+    if !previous.is_empty() {
+        recap.push_str(&format!(
+            " ({:+} on the {} before)",
+            puzzles.len() as isize - previous.len() as isize,
+            if kind == "weekly" { "week" } else { "month" }
+        ));
+    }
+    recap.push('.');
+
+    // Only players that showed up for at least half the period can have the best average.
+    let regulars = players
+        .values()
+        .filter(|player| player.days.len() * 2 >= days)
+        .collect::<Vec<_>>();
+    if let Some(best) = regulars
+        .iter()
+        .map(|player| player.average())
+        .min_by(f64::total_cmp)
+    {
+        let names = regulars
+            .iter()
+            .filter(|player| player.average() == best)
+            .map(|player| format!("**{}**", player.name))
+            .collect::<Vec<_>>();
+        recap.push_str(&format!(
+            "\n🏆 Best average: {} at {:.2} attempts",
+            names.oxford_and(),
+            best
+        ));
+    }
+
+    if let Some(most) = players
+        .values()
+        .map(|player| player.wins)
+        .max()
+        .filter(|wins| *wins > 0)
+    {
+        let names = players
+            .values()
+            .filter(|player| player.wins == most)
+            .map(|player| format!("**{}**", player.name))
+            .collect::<Vec<_>>();
+        recap.push_str(&format!(
+            "\n🥇 Most daily wins: {} with {}",
+            names.oxford_and(),
+            pluralizer::pluralize("win", most as isize, true)
+        ));
+    }
+
+    if let Some((player, improvement)) = players
+        .iter()
+        .filter(|(_, player)| player.scores.len() >= 2)
+        .filter_map(|(player_id, player)| {
+            let before = previous_players.get(player_id)?;
+            (before.scores.len() >= 2).then(|| (player, before.average() - player.average()))
+        })
+        .filter(|(_, improvement)| *improvement > 0.0)
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+    {
+        recap.push_str(&format!(
+            "\n📈 Most improved: **{}**, {:.2} attempts better than before",
+            player.name, improvement
+        ));
+    }
+
+    let day_offsets = puzzles.iter().map(|row| row.puzzle.day_offset);
+    if let (Some(from), Some(to)) = (day_offsets.clone().min(), day_offsets.max()) {
+        if let Some(hardest) = difficulty::describe_hardest(from, to, mode)? {
+            recap.push_str(&format!("\n😰 Hardest day: {}", hardest));
+        }
+    }
+
+    let mut perfect = players
+        .values()
+        .filter(|player| player.days.len() >= days)
+        .map(|player| format!("**{}**", player.name))
+        .collect::<Vec<_>>();
+    if !perfect.is_empty() {
+        perfect.sort();
+        recap.push_str(&format!(
+            "\n📅 Perfect attendance: {}",
+            perfect.oxford_and()
+        ));
+    }
+
+    Ok(Some(recap))
+}
+
+fn summarize(puzzles: &[PuzzleRow]) -> HashMap<u64, PlayerSummary<'_>> {
+    let wins = daily_wins(puzzles, Ranking::current());
+
+    let mut players: HashMap<u64, PlayerSummary> = HashMap::new();
+    for row in puzzles {
+        let player = players
+            .entry(row.submitted_by)
+            .or_insert_with(|| PlayerSummary {
+                name: &row.submitter,
+                days: HashSet::new(),
+                scores: Vec::new(),
+                wins: wins.get(&row.submitted_by).copied().unwrap_or_default(),
+            });
+        player.days.insert(row.puzzle.day_offset);
+        player.scores.push(row.puzzle.score());
+    }
+
+    players
+}
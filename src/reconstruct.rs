@@ -0,0 +1,121 @@
+use crate::wordle::{consistent_guesses, is_answer, to_word, Puzzle, Tile};
+use crate::{find_user_preference, get_current_puzzle, send_direct_message, set_user_preference};
+use hank_pdk::{warn, Hank};
+use hank_types::message::Message;
+
+const PREFERENCE: &str = "reconstruct";
+
+/// Opt in or out of having guesses reconstructed from posted boards.
+pub fn set_opt_in(message: Message, enabled: bool) {
+    let Some(ref user) = message.author else {
+        return;
+    };
+
+    if let Err(e) = set_user_preference(user, PREFERENCE, &enabled.to_string()) {
+        warn!(
+            "couldn't save {}'s {} preference: {}",
+            user.name, PREFERENCE, e
+        );
+        return;
+    }
+
+    Hank::respond(
+        if enabled {
+            "I'll DM you what I think your guesses were when you post your results!"
+        } else {
+            "I'll stop guessing your guesses."
+        }
+        .into(),
+        message,
+    );
+}
+
+/// Work out which words could have produced each row of a posted board. Rows that no word could
+/// have produced are flagged, and users that opted in are sent a guess at what they played.
+pub fn respond_to_board(message: Message, puzzle: &Puzzle) {
+    let Some(ref user) = message.author else {
+        return;
+    };
+
+    let current = get_current_puzzle(false);
+    let Ok(solution) = to_word(&current.solution) else {
+        return;
+    };
+    if puzzle.day_offset != current.days_since_launch {
+        return;
+    }
+
+    let rows = puzzle
+        .board
+        .board
+        .iter()
+        .map(|row| consistent_guesses(row, &solution))
+        .collect::<Vec<_>>();
+
+    let impossible = rows
+        .iter()
+        .enumerate()
+        .filter(|(_, guesses)| guesses.is_empty())
+        .map(|(i, _)| (i + 1).to_string())
+        .collect::<Vec<_>>();
+    if !impossible.is_empty() {
+        Hank::respond(
+            format!(
+                "🤨 No word in the dictionary could make {} {} of that board, are you sure it's real?",
+                pluralizer::pluralize("row", impossible.len() as isize, false),
+                impossible.join(", ")
+            ),
+            message,
+        );
+        return;
+    }
+
+    let opted_in = find_user_preference(&user.id.to_string(), PREFERENCE)
+        .ok()
+        .flatten()
+        .is_some_and(|value| value == "true");
+    if !opted_in {
+        return;
+    }
+
+    // The solved row would give the answer away, so it's left out.
+    let guesses = puzzle
+        .board
+        .board
+        .iter()
+        .zip(&rows)
+        .filter(|(row, _)| !row.iter().all(|tile| *tile == Tile::Green))
+        .map(|(row, guesses)| {
+            // Prefer words that could be answers, they're more likely to be what someone played.
+            let likely = guesses
+                .iter()
+                .find(|guess| is_answer(guess))
+                .unwrap_or(&guesses[0]);
+            format!(
+                "{} {}{}",
+                row.iter()
+                    .map(|tile| tile.render(puzzle.board.theme))
+                    .collect::<String>(),
+                String::from_utf8_lossy(likely).to_uppercase(),
+                if guesses.len() > 1 {
+                    format!(" (or one of {} others)", guesses.len() - 1)
+                } else {
+                    String::new()
+                }
+            )
+        })
+        .collect::<Vec<_>>();
+    if guesses.is_empty() {
+        return;
+    }
+
+    // Sent privately since the guesses would spoil the puzzle for anyone that hasn't played yet.
+    send_direct_message(
+        user,
+        format!(
+            "For Wordle #{} you probably guessed…\n{}",
+            puzzle.day_offset,
+            guesses.join("\n")
+        ),
+    );
+}
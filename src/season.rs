@@ -0,0 +1,344 @@
+use crate::mode::Mode;
+use crate::points::standings;
+use crate::wordle::ordinal;
+use crate::{announce, check_admin, find_setting, set_setting};
+use anyhow::{anyhow, bail, Result};
+use chrono::{Datelike, Months, NaiveDate};
+use hank_pdk::{info, warn, Hank};
+use hank_types::database::PreparedStatement;
+use hank_types::message::Message;
+use serde::Deserialize;
+
+const LENGTHS: [&str; 4] = ["week", "month", "quarter", "year"];
+const DEFAULT_LENGTH: &str = "month";
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct SeasonRow {
+    id: u32,
+    starts_on: NaiveDate,
+    ends_on: NaiveDate,
+    champion_id: Option<u64>,
+    champion: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SeasonStandingRow {
+    season_id: u32,
+    starts_on: NaiveDate,
+    ends_on: NaiveDate,
+    rank: u32,
+    points: f64,
+}
+
+pub fn install() {
+    let query = "
+CREATE TABLE IF NOT EXISTS season (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    starts_on TEXT NOT NULL,
+    ends_on TEXT NOT NULL,
+    champion_id INTEGER,
+    champion TEXT,
+    archived_at TEXT,
+    UNIQUE(starts_on)
+);
+";
+    let _ = Hank::db_query(PreparedStatement::new(query).build());
+
+    let query = "
+CREATE TABLE IF NOT EXISTS season_standing (
+    season_id INTEGER NOT NULL,
+    mode TEXT NOT NULL,
+    player_id INTEGER NOT NULL,
+    player TEXT NOT NULL,
+    rank INTEGER NOT NULL,
+    points REAL NOT NULL,
+    days INTEGER NOT NULL,
+    PRIMARY KEY(season_id, mode, player_id)
+);
+";
+    let _ = Hank::db_query(PreparedStatement::new(query).build());
+}
+
+/// The day the current season started.
+pub fn current_start() -> NaiveDate {
+    match find_last_season() {
+        Ok(Some(season)) => season.ends_on + chrono::Duration::days(1),
+        Ok(None) => period_start(Hank::datetime().date_naive(), &length()),
+        Err(e) => {
+            warn!("couldn't look up the last season: {}", e);
+            period_start(Hank::datetime().date_naive(), &length())
+        }
+    }
+}
+
+/// Archive every season that has finished and announce its champion.
+pub fn close_seasons() {
+    let today = Hank::datetime().date_naive();
+
+    loop {
+        let start = current_start();
+        let end = period_end(start, &length());
+        if end >= today {
+            return;
+        }
+
+        info!("Closing the season from {} to {}...", start, end);
+        match archive_season(start, end) {
+            Ok(announcement) => announce(announcement),
+            Err(e) => {
+                warn!("couldn't close the season from {} to {}: {}", start, end, e);
+                return;
+            }
+        }
+    }
+}
+
+/// Show the current season and the user's past seasons and titles in the mode.
+pub fn seasons(message: Message, mode: Mode) {
+    let Some(ref user) = message.author else {
+        return;
+    };
+
+    let start = current_start();
+    let end = period_end(start, &length());
+    let mut response = format!(
+        "**This season{}** runs from {} to {}",
+        mode.title(),
+        start,
+        end
+    );
+    if let Ok(standings) = standings(start, Hank::datetime().date_naive(), mode) {
+        if let Some((rank, standing)) = standings
+            .iter()
+            .enumerate()
+            .find(|(_, standing)| user.id == standing.player_id.to_string())
+        {
+            response.push_str(&format!(
+                ", you're in {} place with {}",
+                ordinal(rank + 1),
+                pluralizer::pluralize("point", standing.points.round() as isize, true)
+            ));
+        }
+    }
+
+    let history = match find_season_standings(&user.id.to_string(), mode) {
+        Ok(history) => history,
+        Err(e) => {
+            warn!("couldn't look up {}'s seasons: {}", user.name, e);
+            return;
+        }
+    };
+
+    if history.is_empty() {
+        response.push_str("\n\nYou haven't finished a season yet.");
+    } else {
+        let championships = history.iter().filter(|season| season.rank == 1).count();
+        let podiums = history.iter().filter(|season| season.rank <= 3).count();
+        let mut titles = Vec::new();
+        if championships > 0 {
+            titles.push(format!("🏆 {}x champion", championships));
+        }
+        if podiums > 0 {
+            titles.push(format!(
+                "🏅 {}",
+                pluralizer::pluralize("podium", podiums as isize, true)
+            ));
+        }
+        if !titles.is_empty() {
+            response.push_str(&format!("\n{}", titles.join(", ")));
+        }
+
+        response.push_str(&format!("\n\n**Your seasons{}**", mode.title()));
+        for season in history {
+            response.push_str(&format!(
+                "\nSeason {} ({} to {}): {} with {}",
+                season.season_id,
+                season.starts_on,
+                season.ends_on,
+                ordinal(season.rank as usize),
+                pluralizer::pluralize("point", season.points.round() as isize, true)
+            ));
+        }
+    }
+
+    Hank::respond(response, message);
+}
+
+/// Change how long seasons last, starting with the current one.
+pub fn set_length(message: Message, length: &str) {
+    if !check_admin(&message) {
+        return;
+    }
+
+    if !LENGTHS.contains(&length) {
+        Hank::respond(
+            format!("Seasons can last a {}", LENGTHS.join(", ")),
+            message,
+        );
+        return;
+    }
+
+    if let Err(e) = set_setting("season_length", length) {
+        warn!("couldn't set the season length: {}", e);
+        return;
+    }
+
+    Hank::respond(
+        format!(
+            "Seasons now last a {}, this one ends on {}.",
+            length,
+            period_end(current_start(), length)
+        ),
+        message,
+    );
+}
+
+/// Archive the season's standings in every mode, crowning the overall leader champion.
+///
+/// The season is only marked as archived once all of its standings are in, so if archiving fails
+/// part way through, trying again picks up the same season and skips the standings already saved.
+fn archive_season(start: NaiveDate, end: NaiveDate) -> Result<String> {
+    let overall = standings(start, end, Mode::All)?;
+    let champion = overall.first();
+
+    let query = "
+INSERT INTO season (starts_on, ends_on, champion_id, champion)
+VALUES (?, ?, NULLIF(?, ''), NULLIF(?, ''))
+ON CONFLICT(starts_on) DO UPDATE
+SET ends_on = excluded.ends_on, champion_id = excluded.champion_id, champion = excluded.champion
+RETURNING *
+";
+    let statement = PreparedStatement::new(query)
+        .values([
+            start.to_string(),
+            end.to_string(),
+            champion
+                .map(|standing| standing.player_id.to_string())
+                .unwrap_or_default(),
+            champion
+                .map(|standing| standing.player.clone())
+                .unwrap_or_default(),
+        ])
+        .build();
+    let Some(season) = Hank::db_fetch::<SeasonRow>(statement)
+        .map_err(|e| anyhow!(e))?
+        .into_iter()
+        .next()
+    else {
+        bail!("season wasn't created");
+    };
+
+    for mode in Mode::EVERY {
+        let standings = standings(start, end, mode)?;
+        for (rank, standing) in standings.iter().enumerate() {
+            let query = "
+INSERT INTO season_standing (season_id, mode, player_id, player, rank, points, days)
+VALUES (?, ?, ?, ?, ?, ?, ?)
+ON CONFLICT(season_id, mode, player_id) DO NOTHING
+";
+            let statement = PreparedStatement::new(query)
+                .values([
+                    season.id.to_string(),
+                    mode.name().to_string(),
+                    standing.player_id.to_string(),
+                    standing.player.clone(),
+                    (rank + 1).to_string(),
+                    standing.points.to_string(),
+                    standing.days.to_string(),
+                ])
+                .build();
+            Hank::db_query(statement).map_err(|e| anyhow!(e))?;
+        }
+    }
+
+    let statement = PreparedStatement::new("UPDATE season SET archived_at = ? WHERE id = ?")
+        .values([Hank::datetime().to_rfc3339(), season.id.to_string()])
+        .build();
+    Hank::db_query(statement).map_err(|e| anyhow!(e))?;
+
+    let Some(champion) = champion else {
+        return Ok(format!(
+            "Season {} is over, nobody played this time! A new season starts today.",
+            season.id
+        ));
+    };
+
+    let podium = overall
+        .iter()
+        .take(3)
+        .zip(["🥇", "🥈", "🥉"])
+        .map(|(standing, medal)| {
+            format!(
+                "{} <@{}> - {}",
+                medal,
+                standing.player_id,
+                pluralizer::pluralize("point", standing.points.round() as isize, true)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    Ok(format!(
+        "**Season {} is over!** Congratulations to <@{}>, our new champion! 🏆\n\n{}\n\nA new season starts today, good luck everyone!",
+        season.id, champion.player_id, podium
+    ))
+}
+
+fn length() -> String {
+    find_setting("season_length")
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| DEFAULT_LENGTH.into())
+}
+
+/// The start of the period the date is in.
+fn period_start(date: NaiveDate, length: &str) -> NaiveDate {
+    let start = match length {
+        "week" => Some(date - chrono::Duration::days(date.weekday().num_days_from_monday().into())),
+        "quarter" => NaiveDate::from_ymd_opt(date.year(), (date.month0() / 3) * 3 + 1, 1),
+        "year" => NaiveDate::from_ymd_opt(date.year(), 1, 1),
+        _ => date.with_day(1),
+    };
+
+    start.unwrap_or(date)
+}
+
+/// The last day of a season starting on the date.
+fn period_end(start: NaiveDate, length: &str) -> NaiveDate {
+    let next = match length {
+        "week" => Some(start + chrono::Duration::days(7)),
+        "quarter" => start.checked_add_months(Months::new(3)),
+        "year" => start.checked_add_months(Months::new(12)),
+        _ => start.checked_add_months(Months::new(1)),
+    };
+
+    next.unwrap_or(NaiveDate::MAX) - chrono::Duration::days(1)
+}
+
+fn find_last_season() -> Result<Option<SeasonRow>> {
+    let statement = PreparedStatement::new(
+        "SELECT id, starts_on, ends_on, champion_id, champion FROM season WHERE archived_at IS NOT NULL ORDER BY ends_on DESC LIMIT 1",
+    )
+    .build();
+
+    Ok(Hank::db_fetch::<SeasonRow>(statement)
+        .map_err(|e| anyhow!(e))?
+        .into_iter()
+        .next())
+}
+
+fn find_season_standings(player_id: &str, mode: Mode) -> Result<Vec<SeasonStandingRow>> {
+    let query = "
+SELECT st.season_id, s.starts_on, s.ends_on, st.rank, st.points
+FROM season_standing st
+JOIN season s ON s.id = st.season_id
+WHERE st.player_id = ? AND st.mode = ? AND s.archived_at IS NOT NULL
+ORDER BY s.ends_on DESC
+";
+    let statement = PreparedStatement::new(query)
+        .values([player_id.to_string(), mode.name().to_string()])
+        .build();
+
+    Hank::db_fetch::<SeasonStandingRow>(statement).map_err(|e| anyhow!(e))
+}
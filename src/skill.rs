@@ -0,0 +1,201 @@
+use crate::mode::Mode;
+use crate::wordle::{analyze, score_word, to_word, RowAnalysis};
+use crate::{find_puzzle_by_user_and_day_offset, get_current_puzzle, is_direct_message};
+use anyhow::{anyhow, Result};
+use hank_pdk::{warn, Hank};
+use hank_types::database::PreparedStatement;
+use hank_types::message::Message;
+use hank_types::user::User;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+struct SkillRow {
+    player: String,
+    games: u32,
+    skill: f64,
+    luck: f64,
+}
+
+pub fn install() {
+    let query = "
+CREATE TABLE IF NOT EXISTS analysis (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    player TEXT NOT NULL,
+    player_id INTEGER NOT NULL,
+    day_offset INTEGER NOT NULL,
+    guesses TEXT NOT NULL,
+    skill REAL NOT NULL,
+    luck REAL NOT NULL,
+    analyzed_at TEXT NOT NULL,
+    UNIQUE(player_id, day_offset)
+);
+";
+    let _ = Hank::db_query(PreparedStatement::new(query).build());
+}
+
+/// Analyze the guesses behind the result the user posted today.
+pub fn analyze_guesses(message: Message, guesses: &[&str]) {
+    let Some(ref user) = message.author else {
+        return;
+    };
+
+    if !is_direct_message(&message) {
+        Hank::respond(
+            "DM me your guesses with `wordle analyze WORD WORD…` so you don't spoil the puzzle!"
+                .into(),
+            message,
+        );
+        return;
+    }
+
+    let current = get_current_puzzle(false);
+    let puzzle = match find_puzzle_by_user_and_day_offset(user, current.days_since_launch) {
+        Ok(Some(row)) => row.puzzle,
+        Ok(None) => {
+            Hank::respond(
+                "Post today's Wordle result first, then DM me the words you guessed.".into(),
+                message,
+            );
+            return;
+        }
+        Err(e) => {
+            warn!("couldn't look up {}'s puzzle: {}", user.name, e);
+            return;
+        }
+    };
+
+    // The guesses have to reproduce the board that was posted.
+    let Ok(solution) = to_word(&current.solution) else {
+        return;
+    };
+    let board = guesses
+        .iter()
+        .map(|guess| to_word(guess).map(|guess| score_word(&guess, &solution).to_vec()))
+        .collect::<Result<Vec<_>>>();
+    if !board.is_ok_and(|board| board == puzzle.board.board) {
+        Hank::respond(
+            "Those guesses don't match the board you posted today.".into(),
+            message,
+        );
+        return;
+    }
+
+    let rows = match analyze(guesses, &current.solution, puzzle.hard_mode) {
+        Ok(rows) => rows,
+        Err(e) => {
+            Hank::respond(format!("I couldn't analyze that game: {}", e), message);
+            return;
+        }
+    };
+
+    let skill = rows.iter().map(RowAnalysis::skill).sum::<f64>() / rows.len() as f64;
+    let luck = rows.iter().map(RowAnalysis::luck).sum::<f64>();
+    if let Err(e) = insert_analysis(user, current.days_since_launch, guesses, skill, luck) {
+        warn!("couldn't save {}'s analysis: {}", user.name, e);
+    }
+
+    Hank::respond(
+        format!(
+            "**Wordle #{} analysis**\n{}\n\nSkill {:.0}/100, luck {:+.1} bits",
+            current.days_since_launch,
+            rows.iter().map(render).collect::<Vec<_>>().join("\n"),
+            skill,
+            luck
+        ),
+        message,
+    );
+}
+
+/// Rank users by the average skill of their analyzed games.
+pub fn leaderboard(message: Message, mode: Mode) {
+    let query = format!(
+        "
+SELECT a.player, COUNT(*) AS games, AVG(a.skill) AS skill, AVG(a.luck) AS luck
+FROM analysis a
+LEFT JOIN puzzle p ON p.submitted_by = a.player_id AND p.day_offset = a.day_offset
+WHERE {}
+GROUP BY a.player_id
+ORDER BY skill DESC
+LIMIT 10
+",
+        mode.condition("p.hard_mode")
+    );
+    let rows = match Hank::db_fetch::<SkillRow>(PreparedStatement::new(query).build()) {
+        Ok(rows) => rows,
+        Err(e) => {
+            warn!("couldn't look up skill leaderboard: {}", e);
+            return;
+        }
+    };
+
+    if rows.is_empty() {
+        Hank::respond(
+            "Nobody has analyzed a game yet, DM me `wordle analyze` with your guesses after posting your result.".into(),
+            message,
+        );
+        return;
+    }
+
+    let mut response = format!("**Most Skillful Wordlers{}**\n", mode.title());
+    for (i, row) in rows.iter().enumerate() {
+        response.push_str(&format!(
+            "{}. {} - {:.0} skill, {:+.1} luck over {}\n",
+            i + 1,
+            row.player,
+            row.skill,
+            row.luck,
+            pluralizer::pluralize("game", row.games as isize, true)
+        ));
+    }
+
+    Hank::respond(response, message);
+}
+
+fn render(row: &RowAnalysis) -> String {
+    let guess = String::from_utf8_lossy(&row.guess).to_uppercase();
+    let best = String::from_utf8_lossy(&row.best).to_uppercase();
+
+    format!(
+        "`{}` skill {:.0}, luck {:+.1}: {} → {} left{}",
+        guess,
+        row.skill(),
+        row.luck(),
+        row.remaining_before,
+        row.remaining_after,
+        if row.remaining_after > 0 && guess != best {
+            format!(" (best was `{}`)", best)
+        } else {
+            String::new()
+        }
+    )
+}
+
+fn insert_analysis(
+    user: &User,
+    day_offset: u32,
+    guesses: &[&str],
+    skill: f64,
+    luck: f64,
+) -> Result<()> {
+    let query = "
+INSERT INTO analysis (player, player_id, day_offset, guesses, skill, luck, analyzed_at)
+VALUES (?, ?, ?, ?, ?, ?, ?)
+ON CONFLICT(player_id, day_offset) DO UPDATE
+SET guesses = excluded.guesses, skill = excluded.skill, luck = excluded.luck, analyzed_at = excluded.analyzed_at
+";
+    let statement = PreparedStatement::new(query)
+        .values([
+            user.name.clone(),
+            user.id.to_string(),
+            day_offset.to_string(),
+            guesses.join(" "),
+            skill.to_string(),
+            luck.to_string(),
+            Hank::datetime().to_rfc3339(),
+        ])
+        .build();
+
+    Hank::db_query(statement)
+        .map(|_| ())
+        .map_err(|e| anyhow!(e))
+}
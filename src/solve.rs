@@ -0,0 +1,60 @@
+use crate::wordle::{best_guess, candidates, parse_feedback};
+use hank_pdk::Hank;
+use hank_types::message::Message;
+
+/// How many of the remaining candidates are listed.
+const MAX_LISTED: usize = 20;
+
+/// Suggest the next guess given the guesses made so far and the tiles they scored.
+pub fn solve(message: Message, args: &[&str], hard_mode: bool) {
+    let history = match parse_feedback(args) {
+        Ok(history) => history,
+        Err(e) => {
+            Hank::respond(
+                format!(
+                    "{}\n\nUsage: `wordle solve [hard] crane:bygbb slate:ggbbb`, use g for green, y for yellow and b for absent letters.",
+                    e
+                ),
+                message,
+            );
+            return;
+        }
+    };
+
+    let candidates = candidates(&history);
+    if candidates.is_empty() {
+        Hank::respond(
+            "No answers match those tiles, double check them?".into(),
+            message,
+        );
+        return;
+    }
+
+    let listed = candidates
+        .iter()
+        .take(MAX_LISTED)
+        .map(|word| String::from_utf8_lossy(word).to_uppercase())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut response = format!(
+        "**{}** left: {}{}",
+        pluralizer::pluralize("possible answer", candidates.len() as isize, true),
+        listed,
+        if candidates.len() > MAX_LISTED {
+            ", …"
+        } else {
+            ""
+        }
+    );
+
+    if let Some((guess, information)) = best_guess(&candidates, &history, hard_mode) {
+        response.push_str(&format!(
+            "\nBest next guess{}: **{}** ({:.2} bits expected)",
+            if hard_mode { " in hard mode" } else { "" },
+            String::from_utf8_lossy(&guess).to_uppercase(),
+            information
+        ));
+    }
+
+    Hank::respond(response, message);
+}
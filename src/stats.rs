@@ -0,0 +1,148 @@
+use crate::mode::Mode;
+use crate::wrapped::longest_streak;
+use crate::{find_puzzles_between, find_user_theme, PuzzleRow};
+use anyhow::{anyhow, Result};
+use hank_pdk::{warn, Hank};
+use hank_types::database::PreparedStatement;
+use hank_types::message::Message;
+
+/// Show a player's guess distribution and streaks.
+pub fn stats(message: Message, user_id: Option<String>, mode: Mode) {
+    let Some(user_id) = user_id.or_else(|| message.author.as_ref().map(|user| user.id.to_string()))
+    else {
+        return;
+    };
+
+    let all = match find_puzzles_by_player(&user_id) {
+        Ok(puzzles) => puzzles,
+        Err(e) => {
+            warn!("couldn't look up puzzles for {}: {}", user_id, e);
+            return;
+        }
+    };
+    let hard_mode_rate = all.iter().filter(|row| row.puzzle.hard_mode).count() as f64
+        / all.len().max(1) as f64
+        * 100.0;
+    let puzzles = all
+        .into_iter()
+        .filter(|row| mode.includes(row.puzzle.hard_mode))
+        .collect::<Vec<_>>();
+
+    let Some(last) = puzzles.last() else {
+        Hank::respond(
+            format!(
+                "<@{}> hasn't posted any Wordles{} yet.",
+                user_id,
+                mode.title()
+            ),
+            message,
+        );
+        return;
+    };
+
+    let mut distribution = [0u32; 7];
+    for row in &puzzles {
+        let i = if row.puzzle.solved {
+            row.puzzle.attempts.clamp(1, 6) as usize - 1
+        } else {
+            6
+        };
+        distribution[i] += 1;
+    }
+
+    let rows = puzzles.iter().collect::<Vec<_>>();
+    let solved = puzzles.iter().filter(|row| row.puzzle.solved).count();
+    let mut text = format!(
+        "**{}'s stats{}**\nPlayed {}, solved {:.0}%, current streak {}, longest streak {}, {:.0}% of all games in hard mode\n",
+        last.submitter,
+        mode.title(),
+        puzzles.len(),
+        solved as f64 / puzzles.len() as f64 * 100.0,
+        current_streak(&puzzles),
+        longest_streak(&rows),
+        hard_mode_rate
+    );
+    let most = distribution
+        .iter()
+        .copied()
+        .max()
+        .unwrap_or_default()
+        .max(1);
+    for (count, label) in distribution.iter().zip("123456X".chars()) {
+        // Bars are at most 10 squares wide, but anything played shows up.
+        let width = (count * 10).div_ceil(most) as usize;
+        let square = if label == 'X' { "🟥" } else { "🟩" };
+        text.push_str(&format!("\n{} {} {}", label, square.repeat(width), count));
+    }
+
+    // @TODO send a rendered histogram with the text as the fallback once hank messages support
+    // attachments.
+    Hank::respond(text, message);
+}
+
+/// Show everyone's boards for today.
+pub fn wall(message: Message) {
+    let today = Hank::datetime().date_naive();
+    let puzzles = match find_puzzles_between(today, today) {
+        Ok(puzzles) => puzzles,
+        Err(e) => {
+            warn!("couldn't look up today's puzzles: {}", e);
+            return;
+        }
+    };
+
+    if puzzles.is_empty() {
+        Hank::respond("Nobody has posted today's Wordle yet.".into(), message);
+        return;
+    }
+
+    let mut text = String::from("**Today's Wall**");
+    for row in &puzzles {
+        let theme = find_user_theme(&row.submitted_by.to_string());
+        text.push_str(&format!(
+            "\n\n{} {}/6\n{}",
+            row.submitter,
+            if row.puzzle.solved {
+                row.puzzle.attempts.to_string()
+            } else {
+                "X".into()
+            },
+            row.puzzle.board.render(theme)
+        ));
+    }
+
+    // @TODO send a rendered image of the boards with the text as the fallback once hank messages
+    // support attachments.
+    Hank::respond(text, message);
+}
+
+/// How many days in a row the player has played up to today, or yesterday since there's still
+/// time to play today.
+fn current_streak(puzzles: &[PuzzleRow]) -> usize {
+    let today = Hank::datetime().date_naive();
+    let mut expected = match puzzles.last() {
+        Some(row) if row.submitted_date >= today - chrono::Duration::days(1) => row.submitted_date,
+        _ => return 0,
+    };
+
+    let mut streak = 0;
+    for row in puzzles.iter().rev() {
+        if row.submitted_date == expected {
+            streak += 1;
+            expected -= chrono::Duration::days(1);
+        } else if row.submitted_date < expected {
+            break;
+        }
+    }
+
+    streak
+}
+
+fn find_puzzles_by_player(player_id: &str) -> Result<Vec<PuzzleRow>> {
+    let statement =
+        PreparedStatement::new("SELECT * FROM puzzle WHERE submitted_by = ? ORDER BY day_offset")
+            .values([player_id.to_string()])
+            .build();
+
+    Hank::db_fetch::<PuzzleRow>(statement).map_err(|e| anyhow!(e))
+}
@@ -0,0 +1,166 @@
+use crate::deserialize_bool;
+use crate::mode::{Mode, Ranking};
+use anyhow::{anyhow, Result};
+use hank_pdk::{warn, Hank};
+use hank_types::database::PreparedStatement;
+use hank_types::message::Message;
+use serde::Deserialize;
+use std::cmp::Ordering;
+
+#[derive(Debug, Deserialize)]
+struct MatchupRow {
+    a_name: String,
+    a_attempts: u32,
+    #[serde(deserialize_with = "deserialize_bool")]
+    a_solved: bool,
+    #[serde(deserialize_with = "deserialize_bool")]
+    a_hard_mode: bool,
+    b_name: String,
+    b_attempts: u32,
+    #[serde(deserialize_with = "deserialize_bool")]
+    b_solved: bool,
+    #[serde(deserialize_with = "deserialize_bool")]
+    b_hard_mode: bool,
+}
+
+impl MatchupRow {
+    /// Less when a did better.
+    fn compare(&self, ranking: Ranking) -> Ordering {
+        ranking
+            .key(self.a_solved, self.a_attempts, self.a_hard_mode)
+            .cmp(&ranking.key(self.b_solved, self.b_attempts, self.b_hard_mode))
+    }
+}
+
+/// Compare two players over every day they both played.
+pub fn versus(message: Message, a: &str, b: &str, mode: Mode) {
+    if a == b {
+        Hank::respond("Pick two different players!".into(), message);
+        return;
+    }
+
+    let rows = match find_matchups(a, b, mode) {
+        Ok(rows) => rows,
+        Err(e) => {
+            warn!("couldn't look up matchups between {} and {}: {}", a, b, e);
+            return;
+        }
+    };
+
+    let Some(last) = rows.last() else {
+        Hank::respond(
+            format!(
+                "<@{}> and <@{}> haven't played on the same day{} yet.",
+                a,
+                b,
+                mode.title()
+            ),
+            message,
+        );
+        return;
+    };
+    let (a_name, b_name) = (last.a_name.clone(), last.b_name.clone());
+
+    let ranking = Ranking::current();
+    let (mut a_wins, mut b_wins, mut ties) = (0, 0, 0);
+    for row in &rows {
+        match row.compare(ranking) {
+            Ordering::Less => a_wins += 1,
+            Ordering::Greater => b_wins += 1,
+            Ordering::Equal => ties += 1,
+        }
+    }
+
+    let a_average = average(
+        rows.iter()
+            .filter(|row| row.a_solved)
+            .map(|row| row.a_attempts),
+    );
+    let b_average = average(
+        rows.iter()
+            .filter(|row| row.b_solved)
+            .map(|row| row.b_attempts),
+    );
+    let a_fails = rows.iter().filter(|row| !row.a_solved).count();
+    let b_fails = rows.iter().filter(|row| !row.b_solved).count();
+    let a_hard_mode = rows.iter().filter(|row| row.a_hard_mode).count() * 100 / rows.len();
+    let b_hard_mode = rows.iter().filter(|row| row.b_hard_mode).count() * 100 / rows.len();
+
+    // The streak is how many of the most recent days in a row the same player won.
+    let mut results = rows.iter().rev().map(|row| row.compare(ranking));
+    let streak = match results.next() {
+        Some(Ordering::Equal) | None => "Last time was a tie.".to_string(),
+        Some(leader) => {
+            let days = 1 + results.take_while(|result| *result == leader).count();
+            format!(
+                "{} has won the last {}.",
+                if leader == Ordering::Less {
+                    &a_name
+                } else {
+                    &b_name
+                },
+                pluralize_days(days)
+            )
+        }
+    };
+
+    Hank::respond(
+        format!(
+            "**{} vs {}{}** over {}\n{} - {} ({} {})\nAverage attempts: {} vs {}\nFails: {} vs {}\nHard mode: {}% vs {}%\n{}",
+            a_name,
+            b_name,
+            mode.title(),
+            pluralize_days(rows.len()),
+            a_wins,
+            b_wins,
+            ties,
+            pluralizer::pluralize("tie", ties as isize, false),
+            a_average,
+            b_average,
+            a_fails,
+            b_fails,
+            a_hard_mode,
+            b_hard_mode,
+            streak
+        ),
+        message,
+    );
+}
+
+/// The average attempts of the solved days.
+fn average(attempts: impl Iterator<Item = u32>) -> String {
+    let attempts = attempts.collect::<Vec<_>>();
+    if attempts.is_empty() {
+        return "-".into();
+    }
+
+    format!(
+        "{:.2}",
+        attempts.iter().sum::<u32>() as f64 / attempts.len() as f64
+    )
+}
+
+fn pluralize_days(days: usize) -> String {
+    pluralizer::pluralize("day", days as isize, true)
+}
+
+fn find_matchups(a: &str, b: &str, mode: Mode) -> Result<Vec<MatchupRow>> {
+    let query = format!(
+        "
+SELECT
+    a.submitter AS a_name, a.attempts AS a_attempts, a.solved AS a_solved, a.hard_mode AS a_hard_mode,
+    b.submitter AS b_name, b.attempts AS b_attempts, b.solved AS b_solved, b.hard_mode AS b_hard_mode
+FROM puzzle a
+JOIN puzzle b ON b.day_offset = a.day_offset
+WHERE a.submitted_by = ? AND b.submitted_by = ? AND {} AND {}
+ORDER BY a.day_offset ASC
+",
+        mode.condition("a.hard_mode"),
+        mode.condition("b.hard_mode")
+    );
+    let statement = PreparedStatement::new(query)
+        .values([a.to_string(), b.to_string()])
+        .build();
+
+    Hank::db_fetch::<MatchupRow>(statement).map_err(|e| anyhow!(e))
+}
@@ -0,0 +1,108 @@
+use crate::wordle::{
+    best_guess, candidates, dictionary, expected_information, score_word, to_word, Feedback, Tile,
+    Word,
+};
+use anyhow::Result;
+
+/// How a single guess in a game compared to the best guess available at the time.
+#[derive(Clone, Debug)]
+pub struct RowAnalysis {
+    pub guess: Word,
+    pub best: Word,
+    /// Answers that were still possible before the guess.
+    pub remaining_before: usize,
+    /// Answers that were still possible after the guess.
+    pub remaining_after: usize,
+    /// Bits the guess was expected to give.
+    pub information: f64,
+    /// Bits the best guess was expected to give.
+    pub best_information: f64,
+    /// Bits the guess actually gave.
+    pub actual_information: f64,
+}
+
+impl RowAnalysis {
+    /// How close the guess was to the best one, out of 100.
+    pub fn skill(&self) -> f64 {
+        if self.best_information <= 0.0 {
+            return if self.remaining_after == 0 {
+                100.0
+            } else {
+                0.0
+            };
+        }
+
+        (self.information / self.best_information * 100.0).min(100.0)
+    }
+
+    /// Bits more (or less) than the guess was expected to give.
+    pub fn luck(&self) -> f64 {
+        self.actual_information - self.information
+    }
+}
+
+/// Analyze each guess of a game against the solution, `remaining_after` is 0 once it's solved. The
+/// solution is always one of the remaining answers, even if it's newer than the answer list.
+pub fn analyze(guesses: &[&str], solution: &str, hard_mode: bool) -> Result<Vec<RowAnalysis>> {
+    let solution = to_word(solution)?;
+    let remaining = |history: &[Feedback]| {
+        let mut words = candidates(history);
+        if !words.contains(&solution) {
+            words.push(solution);
+        }
+        words
+    };
+
+    let mut history: Vec<Feedback> = Vec::with_capacity(guesses.len());
+    let mut rows = Vec::with_capacity(guesses.len());
+
+    for guess in guesses {
+        let guess = to_word(guess)?;
+        let before = remaining(&history);
+        let (best, best_information) =
+            best_guess(&before, &history, hard_mode).unwrap_or((solution, 0.0));
+
+        history.push((guess, score_word(&guess, &solution)));
+        let remaining_after = if guess == solution {
+            0
+        } else {
+            remaining(&history).len()
+        };
+
+        rows.push(RowAnalysis {
+            guess,
+            best,
+            remaining_before: before.len(),
+            remaining_after,
+            information: expected_information(&guess, &before),
+            best_information,
+            actual_information: (before.len() as f64 / remaining_after.max(1) as f64).log2(),
+        });
+    }
+
+    Ok(rows)
+}
+
+/// Every dictionary word that would have scored the row of tiles against the solution.
+pub fn consistent_guesses(row: &[Tile], solution: &Word) -> Vec<Word> {
+    dictionary()
+        .iter()
+        .filter(|guess| score_word(guess, solution) == row)
+        .copied()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn analyzes_solutions_missing_from_the_answer_list() {
+        let rows = analyze(&["slate", "snafu"], "snafu", false).unwrap();
+
+        assert_eq!(rows.len(), 2);
+        assert!(rows[0].remaining_after >= 1);
+        assert_eq!(rows[1].remaining_before, rows[0].remaining_after);
+        assert_eq!(rows[1].remaining_after, 0);
+    }
+}
@@ -0,0 +1,191 @@
+use crate::wordle::Tile;
+use anyhow::{bail, Result};
+
+pub const WORD_LENGTH: usize = 5;
+
+/// A word normalized to its lowercase ASCII bytes.
+pub type Word = [u8; WORD_LENGTH];
+
+/// Normalize a word so it can be scored, rejecting anything that isn't five letters.
+pub fn to_word(word: &str) -> Result<Word> {
+    let word = word.trim().to_ascii_lowercase();
+    if !word.bytes().all(|b| b.is_ascii_lowercase()) {
+        bail!("{} contains characters that aren't letters", word);
+    }
+
+    match word.as_bytes().try_into() {
+        Ok(word) => Ok(word),
+        Err(_) => bail!("{} isn't {} letters long", word, WORD_LENGTH),
+    }
+}
+
+/// Score a guess against the solution the same way NYT does.
+///
+/// Greens are assigned first, then the remaining letters are marked yellow from left to right
+/// only while the solution still has unmatched copies of that letter, so a repeated letter in
+/// a guess is never shown as present more times than it appears in the solution.
+pub fn score_word(guess: &Word, solution: &Word) -> [Tile; WORD_LENGTH] {
+    let mut tiles = [Tile::Black; WORD_LENGTH];
+    let mut unmatched = [0u8; 26];
+
+    for i in 0..WORD_LENGTH {
+        if guess[i] == solution[i] {
+            tiles[i] = Tile::Green;
+        } else {
+            unmatched[(solution[i] - b'a') as usize] += 1;
+        }
+    }
+
+    for i in 0..WORD_LENGTH {
+        if tiles[i] == Tile::Green {
+            continue;
+        }
+
+        let count = &mut unmatched[(guess[i] - b'a') as usize];
+        if *count > 0 {
+            *count -= 1;
+            tiles[i] = Tile::Yellow;
+        }
+    }
+
+    tiles
+}
+
+/// Describe why a guess breaks hard mode given previous guesses and their feedback, if it does.
+pub fn hard_mode_violation(
+    guess: &Word,
+    history: &[(Word, [Tile; WORD_LENGTH])],
+) -> Option<String> {
+    for (previous, tiles) in history {
+        for i in 0..WORD_LENGTH {
+            if tiles[i] == Tile::Green && guess[i] != previous[i] {
+                return Some(format!(
+                    "{} letter must be {}",
+                    ordinal(i + 1),
+                    (previous[i] as char).to_ascii_uppercase()
+                ));
+            }
+        }
+
+        // Like NYT, a yellow letter only has to be reused once however many times it was
+        // revealed.
+        for i in 0..WORD_LENGTH {
+            if tiles[i] == Tile::Yellow && !guess.contains(&previous[i]) {
+                return Some(format!(
+                    "Guess must contain {}",
+                    (previous[i] as char).to_ascii_uppercase()
+                ));
+            }
+        }
+    }
+
+    None
+}
+
+/// A number as a position, e.g. 1st, 2nd or 11th.
+pub fn ordinal(n: usize) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+
+    format!("{}{}", n, suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(word: &str) -> Word {
+        to_word(word).unwrap()
+    }
+
+    fn tiles(pattern: &str) -> [Tile; WORD_LENGTH] {
+        let mut tiles = [Tile::Black; WORD_LENGTH];
+        for (tile, c) in tiles.iter_mut().zip(pattern.chars()) {
+            *tile = match c {
+                'g' => Tile::Green,
+                'y' => Tile::Yellow,
+                _ => Tile::Black,
+            };
+        }
+
+        tiles
+    }
+
+    #[test]
+    fn scores_words() {
+        let cases = [
+            ("speed", "abide", "bbyby"),
+            ("abbey", "babes", "yyggb"),
+            ("eerie", "sheet", "yybbb"),
+            ("lolly", "hello", "byggb"),
+            ("aaaaa", "abaca", "gbgbg"),
+            ("crane", "crane", "ggggg"),
+        ];
+
+        for (guess, solution, pattern) in cases {
+            assert_eq!(
+                score_word(&word(guess), &word(solution)),
+                tiles(pattern),
+                "{} against {}",
+                guess,
+                solution
+            );
+        }
+    }
+
+    #[test]
+    fn finds_hard_mode_violations() {
+        let history = |guess: &str, solution: &str| {
+            vec![(word(guess), score_word(&word(guess), &word(solution)))]
+        };
+
+        let cases = [
+            ("crane", "crate", "crone", Some("3rd letter must be A")),
+            ("crane", "crate", "trace", Some("1st letter must be C")),
+            ("crane", "crate", "crabe", None),
+            ("eerie", "sheet", "stack", Some("Guess must contain E")),
+            // Two yellow Es only need one E in the next guess.
+            ("eerie", "sheet", "stole", None),
+            ("speed", "abide", "dealt", None),
+            ("speed", "abide", "delta", None),
+            ("speed", "abide", "mauve", Some("Guess must contain D")),
+        ];
+
+        for (previous, solution, guess, violation) in cases {
+            assert_eq!(
+                hard_mode_violation(&word(guess), &history(previous, solution)).as_deref(),
+                violation,
+                "{} after {} against {}",
+                guess,
+                previous,
+                solution
+            );
+        }
+    }
+
+    #[test]
+    fn formats_ordinals() {
+        let cases = [
+            (1, "1st"),
+            (2, "2nd"),
+            (3, "3rd"),
+            (4, "4th"),
+            (11, "11th"),
+            (12, "12th"),
+            (13, "13th"),
+            (21, "21st"),
+            (22, "22nd"),
+            (23, "23rd"),
+            (111, "111th"),
+        ];
+
+        for (n, expected) in cases {
+            assert_eq!(ordinal(n), expected);
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use crate::wordle::{
+    hard_mode_violation, is_valid_guess, score_word, to_word, Puzzle, PuzzleBoard, Theme, Tile,
+    Word, WORD_LENGTH,
+};
+use anyhow::{bail, Result};
+
+pub const MAX_GUESSES: usize = 6;
+
+const KEYBOARD: [&str; 3] = ["qwertyuiop", "asdfghjkl", "zxcvbnm"];
+
+/// An in-progress or finished game of Wordle against a known solution.
+#[derive(Clone, Debug)]
+pub struct Game {
+    pub solution: String,
+    pub guesses: Vec<String>,
+    pub hard_mode: bool,
+}
+
+impl Game {
+    /// Submit a guess, returning the row of tiles it scored.
+    pub fn guess(&mut self, guess: &str) -> Result<Vec<Tile>> {
+        if self.is_finished() {
+            bail!("this game is already over");
+        }
+
+        let word = to_word(guess)?;
+        let solution = to_word(&self.solution)?;
+        if word != solution && !is_valid_guess(guess) {
+            bail!(
+                "{} isn't in the word list",
+                guess.trim().to_ascii_uppercase()
+            );
+        }
+
+        if self.hard_mode {
+            if let Some(violation) = hard_mode_violation(&word, &self.history()?) {
+                bail!(violation);
+            }
+        }
+
+        let tiles = score_word(&word, &solution);
+        self.guesses
+            .push(String::from_utf8_lossy(&word).into_owned());
+
+        Ok(tiles.to_vec())
+    }
+
+    pub fn rows(&self) -> Vec<Vec<Tile>> {
+        self.history()
+            .map(|history| history.into_iter().map(|(_, t)| t.to_vec()).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.guesses.last() == Some(&self.solution)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.is_solved() || self.guesses.len() >= MAX_GUESSES
+    }
+
+    pub fn board(&self, theme: Theme) -> PuzzleBoard {
+        PuzzleBoard {
+            board: self.rows(),
+            theme,
+        }
+    }
+
+    /// Render each guess next to its row of tiles.
+    pub fn render(&self, theme: Theme) -> String {
+        self.rows()
+            .iter()
+            .zip(&self.guesses)
+            .map(|(row, guess)| {
+                format!(
+                    "{} `{}`",
+                    row.iter().map(|t| t.render(theme)).collect::<String>(),
+                    guess.to_ascii_uppercase()
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    /// Render a keyboard of the letters guessed so far: greens are bold, yellows are underlined
+    /// and letters that aren't in the solution are struck through.
+    pub fn keyboard(&self) -> String {
+        let mut best: [Option<Tile>; 26] = [None; 26];
+        for (guess, tiles) in self.history().unwrap_or_default() {
+            for (letter, tile) in guess.iter().zip(tiles) {
+                let key = &mut best[(letter - b'a') as usize];
+                *key = match (*key, tile) {
+                    (Some(Tile::Green), _) | (_, Tile::Green) => Some(Tile::Green),
+                    (Some(Tile::Yellow), _) | (_, Tile::Yellow) => Some(Tile::Yellow),
+                    _ => Some(Tile::Black),
+                };
+            }
+        }
+
+        KEYBOARD
+            .iter()
+            .map(|row| {
+                row.bytes()
+                    .map(|letter| {
+                        let key = (letter as char).to_ascii_uppercase();
+                        match best[(letter - b'a') as usize] {
+                            Some(Tile::Green) => format!("**{}**", key),
+                            Some(Tile::Yellow) => format!("__{}__", key),
+                            Some(Tile::Black) => format!("~~{}~~", key),
+                            None => key.to_string(),
+                        }
+                    })
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    /// The result of a finished game in the same format Wordle shares it.
+    pub fn share(&self, day_offset: u32, theme: Theme) -> Puzzle {
+        let solved = self.is_solved();
+        Puzzle {
+            day_offset,
+            attempts: if solved {
+                self.guesses.len() as u32
+            } else {
+                MAX_GUESSES as u32
+            },
+            solved,
+            hard_mode: self.hard_mode,
+            board: self.board(theme),
+        }
+    }
+
+    fn history(&self) -> Result<Vec<(Word, [Tile; WORD_LENGTH])>> {
+        let solution = to_word(&self.solution)?;
+        self.guesses
+            .iter()
+            .map(|guess| {
+                let guess = to_word(guess)?;
+                Ok((guess, score_word(&guess, &solution)))
+            })
+            .collect()
+    }
+}
@@ -1,7 +1,17 @@
+mod analysis;
+mod feedback;
+mod game;
 mod puzzle;
 mod puzzle_board;
+mod solver;
 mod tile;
+mod words;
 
+pub use analysis::*;
+pub use feedback::*;
+pub use game::*;
 pub use puzzle::*;
 pub use puzzle_board::*;
+pub use solver::*;
 pub use tile::*;
+pub use words::*;
@@ -13,10 +13,26 @@ pub struct Puzzle {
     pub board: PuzzleBoard,
 }
 
+/// What a failure counts as when comparing results, so it loses to any solve.
+pub const FAIL_SCORE: u32 = 7;
+
+/// How many attempts a result counts as when comparing it with others.
+pub fn score(solved: bool, attempts: u32) -> u32 {
+    if solved {
+        attempts
+    } else {
+        FAIL_SCORE
+    }
+}
+
 impl Puzzle {
     pub fn new(puzzle: impl Into<String>) -> Result<Self> {
         Self::try_from(puzzle.into())
     }
+
+    pub fn score(&self) -> u32 {
+        score(self.solved, self.attempts)
+    }
 }
 
 impl TryFrom<Puzzle> for String {
@@ -39,7 +55,11 @@ impl TryFrom<Puzzle> for String {
         string.push_str(&day_offset);
         string.push(' ');
 
-        string.push_str(&puzzle.attempts.to_string());
+        if puzzle.solved {
+            string.push_str(&puzzle.attempts.to_string());
+        } else {
+            string.push('X');
+        }
         string.push_str("/6");
 
         if puzzle.hard_mode {
@@ -61,9 +81,10 @@ impl TryFrom<String> for Puzzle {
         let mut lines = value.lines();
         let first_line = lines.next().context("couldn't get first line of puzzle")?;
 
-        let re =
-            Regex::new(r"Wordle (?<day_offset>\d+,\d+) (?<attempts>([1-6]|X))\/6(?<hard_mode>\*)?")
-                .context("couldn't construct regex")?;
+        let re = Regex::new(
+            r"Wordle (?<day_offset>\d{1,3}(,\d{3})*) (?<attempts>([1-6]|X))\/6(?<hard_mode>\*)?",
+        )
+        .context("couldn't construct regex")?;
         let Some(captures) = re.captures(first_line) else {
             bail!("couldn't find Wordle header pattern".to_string());
         };
@@ -96,3 +117,46 @@ impl TryFrom<String> for Puzzle {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_headers() {
+        let cases = [
+            ("Wordle 500 3/6", 500, 3, true, false),
+            ("Wordle 1,234 X/6*", 1234, 6, false, true),
+            ("Wordle 7 1/6", 7, 1, true, false),
+        ];
+
+        for (header, day_offset, attempts, solved, hard_mode) in cases {
+            let rows = if solved {
+                "🟩🟩🟩🟩🟩"
+            } else {
+                "⬛⬛⬛⬛⬛"
+            };
+            let rows = vec![rows; attempts as usize].join("\n");
+            let puzzle = Puzzle::new(format!("{}\n\n{}", header, rows)).unwrap();
+
+            assert_eq!(puzzle.day_offset, day_offset, "{}", header);
+            assert_eq!(puzzle.attempts, attempts, "{}", header);
+            assert_eq!(puzzle.solved, solved, "{}", header);
+            assert_eq!(puzzle.hard_mode, hard_mode, "{}", header);
+        }
+    }
+
+    #[test]
+    fn round_trips_shared_results() {
+        for (header, day_offset) in [("500", 500), ("1,234", 1234)] {
+            let puzzle = Puzzle::new(format!(
+                "Wordle {} 3/6\n\n⬛🟨⬛⬛⬛\n⬛🟩🟩⬛⬛\n🟩🟩🟩🟩🟩",
+                header
+            ))
+            .unwrap();
+            let shared = String::try_from(puzzle).unwrap();
+
+            assert_eq!(Puzzle::new(shared).unwrap().day_offset, day_offset);
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use crate::wordle::Tile;
+use crate::wordle::{Theme, Tile};
 use anyhow::{bail, Context as _};
 use serde::{Deserialize, Serialize};
 
@@ -6,11 +6,26 @@ use serde::{Deserialize, Serialize};
 #[serde(try_from = "String", into = "String")]
 pub struct PuzzleBoard {
     pub board: Vec<Vec<Tile>>,
+    pub theme: Theme,
+}
+
+impl PuzzleBoard {
+    /// Render the board with the given theme rather than the one it was shared with.
+    pub fn render(&self, theme: Theme) -> String {
+        self.board
+            .iter()
+            .map(|line| line.iter().map(|t| t.render(theme)).collect::<String>())
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
 }
 
 impl From<Vec<Vec<Tile>>> for PuzzleBoard {
     fn from(board: Vec<Vec<Tile>>) -> Self {
-        PuzzleBoard { board }
+        PuzzleBoard {
+            board,
+            theme: Theme::default(),
+        }
     }
 }
 
@@ -68,22 +83,15 @@ impl TryFrom<String> for PuzzleBoard {
             _ => (),
         }
 
-        Ok(PuzzleBoard { board })
+        Ok(PuzzleBoard {
+            board,
+            theme: Theme::detect(&value),
+        })
     }
 }
 
 impl From<PuzzleBoard> for String {
     fn from(puzzle: PuzzleBoard) -> Self {
-        puzzle
-            .board
-            .into_iter()
-            .map(|line| {
-                line.into_iter()
-                    .map(String::from)
-                    .collect::<Vec<String>>()
-                    .join("")
-            })
-            .collect::<Vec<String>>()
-            .join("\n")
+        puzzle.render(puzzle.theme)
     }
 }
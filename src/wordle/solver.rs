@@ -0,0 +1,202 @@
+use crate::wordle::{
+    answers, dictionary, hard_mode_violation, recorded_answers, score_word, to_word, Tile, Word,
+    WORD_LENGTH,
+};
+use anyhow::{bail, Result};
+
+/// A guess and the tiles it scored.
+pub type Feedback = (Word, [Tile; WORD_LENGTH]);
+
+/// Above this many candidates only answers are considered as the next guess, searching the whole
+/// dictionary is too slow to do inside the plugin and rarely finds anything better.
+const FULL_SEARCH_CANDIDATES: usize = 300;
+
+/// Parse guesses and their feedback, either as a word followed by tile codes (`crane:bygbb` or
+/// `crane bygbb`, with g for green, y for yellow and b, x, w, `.` or `-` for absent) or as
+/// pasted rows of tiles (emoji, or Slack's `:large_green_square:` names) alongside the words
+/// they were for, in the same order.
+pub fn parse_feedback(args: &[impl AsRef<str>]) -> Result<Vec<Feedback>> {
+    let mut words = Vec::new();
+    let mut rows = Vec::new();
+    for arg in args {
+        let arg = arg.as_ref().trim();
+        // Rows are matched first since Slack's emoji names are wrapped in colons too.
+        if let Ok(row) = parse_row(arg) {
+            rows.push(row);
+        } else if let Some((word, row)) = arg
+            .split_once([':', '='])
+            .filter(|(word, _)| to_word(word).is_ok())
+        {
+            words.push(to_word(word)?);
+            rows.push(parse_row(row)?);
+        } else {
+            words.push(to_word(arg)?);
+        }
+    }
+
+    if words.len() != rows.len() {
+        bail!(
+            "got {} guesses but {} rows of tiles",
+            words.len(),
+            rows.len()
+        );
+    }
+
+    Ok(words.into_iter().zip(rows).collect())
+}
+
+/// Every answer that would have given the same feedback.
+pub fn candidates(history: &[Feedback]) -> Vec<Word> {
+    answers()
+        .iter()
+        .chain(&recorded_answers())
+        .filter(|answer| {
+            history
+                .iter()
+                .all(|(guess, tiles)| score_word(guess, answer) == *tiles)
+        })
+        .copied()
+        .collect()
+}
+
+/// The guess that's expected to narrow down the candidates the most, along with how many bits of
+/// information it's expected to give. Guesses that could be the answer win ties.
+pub fn best_guess(
+    candidates: &[Word],
+    history: &[Feedback],
+    hard_mode: bool,
+) -> Option<(Word, f64)> {
+    if candidates.len() <= 2 {
+        return candidates
+            .first()
+            .map(|word| (*word, expected_information(word, candidates)));
+    }
+
+    let pool = if candidates.len() > FULL_SEARCH_CANDIDATES {
+        answers()
+    } else {
+        dictionary()
+    };
+
+    pool.iter()
+        .filter(|guess| !hard_mode || hard_mode_violation(guess, history).is_none())
+        .map(|guess| {
+            let possible = candidates.binary_search(guess).is_ok();
+            (guess, expected_information(guess, candidates), possible)
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1).then(a.2.cmp(&b.2)))
+        .map(|(guess, information, _)| (*guess, information))
+}
+
+/// The entropy, in bits, of the feedback a guess would get across the candidates.
+pub fn expected_information(guess: &Word, candidates: &[Word]) -> f64 {
+    let mut buckets = [0u32; 243];
+    for candidate in candidates {
+        buckets[pattern(&score_word(guess, candidate))] += 1;
+    }
+
+    let total = candidates.len() as f64;
+    buckets
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let p = count as f64 / total;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Encode a row of tiles as a base 3 number.
+fn pattern(tiles: &[Tile; WORD_LENGTH]) -> usize {
+    tiles.iter().fold(0, |pattern, tile| {
+        pattern * 3
+            + match tile {
+                Tile::Black => 0,
+                Tile::Yellow => 1,
+                Tile::Green => 2,
+            }
+    })
+}
+
+fn parse_row(row: &str) -> Result<[Tile; WORD_LENGTH]> {
+    let tiles = if row.starts_with(':') {
+        row.split(':')
+            .filter(|name| !name.is_empty())
+            .map(|name| Tile::try_from(name.to_string()))
+            .collect::<Result<Vec<_>>>()?
+    } else {
+        row.chars()
+            .filter(|c| *c != '\u{fe0f}')
+            .map(|c| match c.to_ascii_lowercase() {
+                'g' => Ok(Tile::Green),
+                'y' => Ok(Tile::Yellow),
+                'b' | 'x' | 'w' | '.' | '-' => Ok(Tile::Black),
+                c => Tile::try_from(c.to_string()),
+            })
+            .collect::<Result<Vec<_>>>()?
+    };
+
+    match tiles.try_into() {
+        Ok(tiles) => Ok(tiles),
+        Err(_) => bail!("{} isn't a row of {} tiles", row, WORD_LENGTH),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feedback(word: &str, pattern: &str) -> Feedback {
+        let tiles = pattern
+            .chars()
+            .map(|c| match c {
+                'g' => Tile::Green,
+                'y' => Tile::Yellow,
+                _ => Tile::Black,
+            })
+            .collect::<Vec<_>>();
+
+        (to_word(word).unwrap(), tiles.try_into().unwrap())
+    }
+
+    #[test]
+    fn parses_feedback() {
+        let slack = ":black_large_square::large_yellow_square::large_green_square:\
+            :black_large_square::black_large_square:";
+        let cases: [(&[&str], Vec<Feedback>); 8] = [
+            (&["crane:bygbb"], vec![feedback("crane", "bygbb")]),
+            (&["CRANE=BYGBB"], vec![feedback("crane", "bygbb")]),
+            (&["crane", "x.g-w"], vec![feedback("crane", "bbgbb")]),
+            (&["crane", "⬛🟨🟩⬛⬛"], vec![feedback("crane", "bygbb")]),
+            (&["crane", "⬜🟦🟧⬜⬜"], vec![feedback("crane", "bygbb")]),
+            (&["crane", slack], vec![feedback("crane", "bygbb")]),
+            (
+                &[&format!("crane:{}", slack)],
+                vec![feedback("crane", "bygbb")],
+            ),
+            (
+                &["crane", "slate", "bygbb", "ggbbb"],
+                vec![feedback("crane", "bygbb"), feedback("slate", "ggbbb")],
+            ),
+        ];
+
+        for (args, expected) in cases {
+            assert_eq!(parse_feedback(args).unwrap(), expected, "{:?}", args);
+        }
+    }
+
+    #[test]
+    fn rejects_bad_feedback() {
+        let cases: [&[&str]; 5] = [
+            &["crane"],
+            &["bygbb"],
+            &["crane:bygb"],
+            &["crane:bygbq"],
+            &["cranes:bygbb"],
+        ];
+
+        for args in cases {
+            assert!(parse_feedback(args).is_err(), "{:?}", args);
+        }
+    }
+}
@@ -1,24 +1,34 @@
 use anyhow::bail;
 use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::str::FromStr;
 
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Tile {
     Black,
     Yellow,
     Green,
 }
 
-impl From<Tile> for String {
-    fn from(value: Tile) -> Self {
+impl Tile {
+    /// Render the tile as the emoji a player using the given theme would have shared.
+    pub fn render(&self, theme: Theme) -> &'static str {
         use Tile::*;
 
-        let tile = match value {
-            Black => "⬛",
-            Yellow => "🟨",
-            Green => "🟩",
-        };
+        match (self, theme.light_mode, theme.high_contrast) {
+            (Black, false, _) => "⬛",
+            (Black, true, _) => "⬜",
+            (Yellow, _, false) => "🟨",
+            (Yellow, _, true) => "🟦",
+            (Green, _, false) => "🟩",
+            (Green, _, true) => "🟧",
+        }
+    }
+}
 
-        tile.into()
+impl From<Tile> for String {
+    fn from(value: Tile) -> Self {
+        value.render(Theme::default()).into()
     }
 }
 
@@ -29,13 +39,190 @@ impl TryFrom<String> for Tile {
         use Tile::*;
 
         Ok(match value.as_str() {
-            "black_large_square" => Black,
-            "large_yellow_square" => Yellow,
-            "large_green_square" => Green,
-            "⬛" => Black,
-            "🟨" => Yellow,
-            "🟩" => Green,
+            "black_large_square" | "white_large_square" => Black,
+            "large_yellow_square" | "large_blue_square" => Yellow,
+            "large_green_square" | "large_orange_square" => Green,
+            "⬛" | "⬜" => Black,
+            "🟨" | "🟦" => Yellow,
+            "🟩" | "🟧" => Green,
             _ => bail!("couldn't convert {} to tile", value),
         })
     }
 }
+
+/// The Wordle display settings a board was shared with.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Theme {
+    pub light_mode: bool,
+    pub high_contrast: bool,
+}
+
+impl Theme {
+    /// Detect the theme from the emoji (or Slack emoji names) in a shared board.
+    pub fn detect(board: &str) -> Self {
+        let light_mode = board.contains('⬜') || board.contains("white_large_square");
+        let high_contrast = ['🟧', '🟦'].iter().any(|c| board.contains(*c))
+            || ["large_orange_square", "large_blue_square"]
+                .iter()
+                .any(|name| board.contains(name));
+
+        Theme {
+            light_mode,
+            high_contrast,
+        }
+    }
+
+    /// Whether a shared board has both a blank and a coloured tile, since a board missing either
+    /// (like a first guess win) looks the same in more than one theme.
+    pub fn identified_by(board: &str) -> bool {
+        let blank = ['⬛', '⬜'].iter().any(|c| board.contains(*c))
+            || ["black_large_square", "white_large_square"]
+                .iter()
+                .any(|name| board.contains(name));
+        let coloured = ['🟨', '🟩', '🟦', '🟧'].iter().any(|c| board.contains(*c))
+            || [
+                "large_yellow_square",
+                "large_green_square",
+                "large_blue_square",
+                "large_orange_square",
+            ]
+            .iter()
+            .any(|name| board.contains(name));
+
+        blank && coloured
+    }
+}
+
+impl Display for Theme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let theme = match (self.light_mode, self.high_contrast) {
+            (false, false) => "dark",
+            (true, false) => "light",
+            (false, true) => "high-contrast",
+            (true, true) => "light-high-contrast",
+        };
+
+        write!(f, "{}", theme)
+    }
+}
+
+impl FromStr for Theme {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (light_mode, high_contrast) = match s {
+            "dark" => (false, false),
+            "light" => (true, false),
+            "high-contrast" => (false, true),
+            "light-high-contrast" => (true, true),
+            _ => bail!("couldn't convert {} to theme", s),
+        };
+
+        Ok(Theme {
+            light_mode,
+            high_contrast,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const THEMES: [Theme; 4] = [
+        Theme {
+            light_mode: false,
+            high_contrast: false,
+        },
+        Theme {
+            light_mode: true,
+            high_contrast: false,
+        },
+        Theme {
+            light_mode: false,
+            high_contrast: true,
+        },
+        Theme {
+            light_mode: true,
+            high_contrast: true,
+        },
+    ];
+
+    #[test]
+    fn parses_tiles() {
+        use Tile::*;
+
+        let cases = [
+            ("⬛", Black),
+            ("⬜", Black),
+            ("🟨", Yellow),
+            ("🟦", Yellow),
+            ("🟩", Green),
+            ("🟧", Green),
+            ("black_large_square", Black),
+            ("white_large_square", Black),
+            ("large_yellow_square", Yellow),
+            ("large_blue_square", Yellow),
+            ("large_green_square", Green),
+            ("large_orange_square", Green),
+        ];
+        for (tile, expected) in cases {
+            assert_eq!(
+                Tile::try_from(tile.to_string()).unwrap(),
+                expected,
+                "{}",
+                tile
+            );
+        }
+
+        assert!(Tile::try_from("🟥".to_string()).is_err());
+    }
+
+    #[test]
+    fn detects_themes() {
+        let cases = [
+            ("⬛🟨⬛⬛⬛\n🟩🟩🟩🟩🟩", THEMES[0]),
+            ("⬜🟨⬜⬜⬜\n🟩🟩🟩🟩🟩", THEMES[1]),
+            ("⬛🟦⬛⬛⬛\n🟧🟧🟧🟧🟧", THEMES[2]),
+            ("⬜🟦⬜⬜⬜\n🟧🟧🟧🟧🟧", THEMES[3]),
+            (
+                ":white_large_square::large_blue_square::white_large_square::white_large_square::white_large_square:",
+                THEMES[3],
+            ),
+        ];
+        for (board, expected) in cases {
+            assert_eq!(Theme::detect(board), expected, "{}", board);
+        }
+    }
+
+    #[test]
+    fn identifies_themes_only_from_mixed_boards() {
+        assert!(Theme::identified_by("⬜🟦⬜⬜⬜\n🟧🟧🟧🟧🟧"));
+        assert!(Theme::identified_by(
+            ":black_large_square::large_green_square::black_large_square::black_large_square::black_large_square:"
+        ));
+
+        // A first guess win and an all black X look the same in more than one theme.
+        assert!(!Theme::identified_by("🟩🟩🟩🟩🟩"));
+        assert!(!Theme::identified_by(&["⬛⬛⬛⬛⬛"; 6].join("\n")));
+    }
+
+    #[test]
+    fn round_trips_themes() {
+        for theme in THEMES {
+            assert_eq!(theme.to_string().parse::<Theme>().unwrap(), theme);
+
+            let tiles = [Tile::Black, Tile::Yellow, Tile::Green];
+            let row = tiles.map(|tile| tile.render(theme)).concat();
+            assert_eq!(Theme::detect(&row), theme, "{}", row);
+            for tile in tiles {
+                assert_eq!(
+                    Tile::try_from(tile.render(theme).to_string()).unwrap(),
+                    tile
+                );
+            }
+        }
+
+        assert!("sepia".parse::<Theme>().is_err());
+    }
+}
@@ -0,0 +1,114 @@
+use crate::wordle::{to_word, Word};
+use miniz_oxide::inflate::decompress_to_vec;
+use std::sync::{Mutex, OnceLock, PoisonError};
+
+static ANSWERS: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/answers.txt.deflate"));
+static ALLOWED: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/allowed.txt.deflate"));
+
+/// Answers that have been used but aren't in the bundled list, which is a snapshot of the original
+/// answers from before NYT started changing them. Sorted alphabetically.
+static RECORDED: Mutex<Vec<Word>> = Mutex::new(Vec::new());
+
+/// Every word that can be a solution, sorted alphabetically.
+pub fn answers() -> &'static [Word] {
+    static WORDS: OnceLock<Vec<Word>> = OnceLock::new();
+
+    WORDS.get_or_init(|| decompress(ANSWERS))
+}
+
+/// Every word that's accepted as a guess, including all of the answers, sorted alphabetically.
+pub fn dictionary() -> &'static [Word] {
+    static WORDS: OnceLock<Vec<Word>> = OnceLock::new();
+
+    WORDS.get_or_init(|| {
+        let mut words = decompress(ALLOWED);
+        words.extend_from_slice(answers());
+        words.sort_unstable();
+        words
+    })
+}
+
+/// Treat words that have been used as answers as possible answers, alongside the bundled list.
+pub fn add_answers<'a>(words: impl IntoIterator<Item = &'a str>) {
+    let mut recorded = RECORDED.lock().unwrap_or_else(PoisonError::into_inner);
+    for word in words.into_iter().filter_map(|word| to_word(word).ok()) {
+        if answers().binary_search(&word).is_ok() {
+            continue;
+        }
+        if let Err(i) = recorded.binary_search(&word) {
+            recorded.insert(i, word);
+        }
+    }
+}
+
+/// Answers that have been used but aren't in the bundled list, sorted alphabetically.
+pub fn recorded_answers() -> Vec<Word> {
+    RECORDED
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+/// Whether the word could be (or has been) a solution.
+pub fn is_answer(word: &Word) -> bool {
+    answers().binary_search(word).is_ok()
+        || RECORDED
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .binary_search(word)
+            .is_ok()
+}
+
+/// Whether the word is accepted as a guess.
+pub fn is_valid_guess(word: &str) -> bool {
+    to_word(word).is_ok_and(|word| dictionary().binary_search(&word).is_ok())
+}
+
+fn decompress(list: &[u8]) -> Vec<Word> {
+    let words = decompress_to_vec(list).expect("bundled word list should decompress");
+
+    let mut words = String::from_utf8_lossy(&words)
+        .lines()
+        .filter_map(|word| to_word(word).ok())
+        .collect::<Vec<Word>>();
+    words.sort_unstable();
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decompresses_word_lists() {
+        assert_eq!(answers().len(), 2314);
+        assert_eq!(decompress(ALLOWED).len(), 10488);
+        assert_eq!(dictionary().len(), 2314 + 10488);
+    }
+
+    #[test]
+    fn accepts_answers_and_allowed_guesses() {
+        for word in ["aback", "cigar", "ZESTY", " crane "] {
+            assert!(is_valid_guess(word), "{} should be a valid guess", word);
+        }
+        for word in ["aahed", "zymic"] {
+            assert!(is_valid_guess(word), "{} should be a valid guess", word);
+        }
+        for word in ["abcde", "crane!", "cran", "cranes"] {
+            assert!(!is_valid_guess(word), "{} shouldn't be a valid guess", word);
+        }
+    }
+
+    #[test]
+    fn recognises_recorded_answers() {
+        let word = |word| to_word(word).unwrap();
+        assert!(is_answer(&word("cigar")));
+        assert!(!is_answer(&word("aahed")));
+
+        // Words that aren't in the bundled list count once they've been used.
+        assert!(!is_answer(&word("squid")));
+        add_answers(["SQUID", "cigar", "squid"]);
+        assert!(is_answer(&word("squid")));
+        assert_eq!(recorded_answers(), vec![word("squid")]);
+    }
+}
@@ -0,0 +1,339 @@
+use crate::mode::{Mode, Ranking};
+use crate::wordle::Theme;
+use crate::{announce, find_puzzles_between, PuzzleRow};
+use anyhow::Result;
+use chrono::{Datelike, NaiveDate};
+use hank_pdk::{warn, Hank};
+use hank_types::message::Message;
+use std::collections::{BTreeMap, HashMap};
+
+/// Announce last year's server recap, run at the start of each year.
+pub fn announce_last_year() {
+    let year = Hank::datetime().year() - 1;
+    let puzzles = match find_puzzles_by_year(year) {
+        Ok(puzzles) => puzzles,
+        Err(e) => {
+            warn!("couldn't look up puzzles for {}: {}", year, e);
+            return;
+        }
+    };
+
+    if !puzzles.is_empty() {
+        announce(format!(
+            "{}\n\nSee your own year with `wordle wrapped {}`!",
+            server_recap(year, &puzzles),
+            year
+        ));
+    }
+}
+
+/// Recap the user's year, followed by the server's.
+pub fn wrapped(message: Message, year: Option<&str>, mode: Mode) {
+    let Some(ref user) = message.author else {
+        return;
+    };
+
+    let year = match year.map(str::parse) {
+        None => Hank::datetime().year(),
+        Some(Ok(year)) => year,
+        Some(Err(_)) => {
+            Hank::respond(
+                "Usage: `wordle wrapped [year] [hard|normal]`".into(),
+                message,
+            );
+            return;
+        }
+    };
+
+    let puzzles = match find_puzzles_by_year(year) {
+        Ok(puzzles) => puzzles
+            .into_iter()
+            .filter(|row| mode.includes(row.puzzle.hard_mode))
+            .collect::<Vec<_>>(),
+        Err(e) => {
+            warn!("couldn't look up puzzles for {}: {}", year, e);
+            return;
+        }
+    };
+
+    let user_id = user.id.to_string();
+    let mine = puzzles
+        .iter()
+        .filter(|row| row.submitted_by.to_string() == user_id)
+        .collect::<Vec<_>>();
+    if mine.is_empty() {
+        Hank::respond(
+            format!("You didn't post any Wordles{} in {}.", mode.title(), year),
+            message,
+        );
+        return;
+    }
+
+    for section in user_recap(year, &user.name, &mine, &puzzles) {
+        Hank::respond(section, message.clone());
+    }
+    Hank::respond(server_recap(year, &puzzles), message);
+}
+
+fn user_recap(year: i32, name: &str, mine: &[&PuzzleRow], everyone: &[PuzzleRow]) -> Vec<String> {
+    let solved = mine.iter().filter(|row| row.puzzle.solved).count();
+    let hard_mode = mine.iter().filter(|row| row.puzzle.hard_mode).count();
+    let average = mine.iter().map(|row| row.puzzle.score()).sum::<u32>() as f64 / mine.len() as f64;
+
+    let mut months: BTreeMap<u32, Vec<u32>> = BTreeMap::new();
+    for row in mine {
+        months
+            .entry(row.submitted_date.month())
+            .or_default()
+            .push(row.puzzle.score());
+    }
+    let best_month = months
+        .iter()
+        .map(|(month, scores)| {
+            (
+                month,
+                scores.iter().sum::<u32>() as f64 / scores.len() as f64,
+            )
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .and_then(|(month, average)| {
+            Some((chrono::Month::try_from(*month as u8).ok()?.name(), average))
+        });
+
+    let ranking = Ranking::current();
+    let wins = mine
+        .first()
+        .and_then(|row| {
+            daily_wins(everyone, ranking)
+                .get(&row.submitted_by)
+                .copied()
+        })
+        .unwrap_or_default();
+
+    let mut overview = format!(
+        "**{}'s {} Wordle Wrapped** 🎁\nYou played **{}** and solved {} of them, averaging {:.2} attempts, {:.0}% in hard mode.",
+        name,
+        year,
+        pluralizer::pluralize("Wordle", mine.len() as isize, true),
+        solved,
+        average,
+        hard_mode as f64 / mine.len() as f64 * 100.0
+    );
+    if let Some((month, average)) = best_month {
+        overview.push_str(&format!(
+            "\nYour best month was **{}** at {:.2} attempts.",
+            month, average
+        ));
+    }
+    overview.push_str(&format!(
+        "\nYour longest streak was **{}**.",
+        pluralizer::pluralize("day", longest_streak(mine) as isize, true)
+    ));
+
+    let mut highlights = format!(
+        "You had the best result of the day **{}**.",
+        pluralizer::pluralize("time", wins as isize, true)
+    );
+    if let Some((pattern, count)) = most_common_opening(mine.iter().copied()) {
+        highlights.push_str(&format!(
+            "\nYour most common first row was {} ({}).",
+            pattern,
+            pluralizer::pluralize("time", count as isize, true)
+        ));
+    }
+    if let Some((nemesis, count)) = nemesis(mine, everyone, ranking) {
+        highlights.push_str(&format!(
+            "\nYour nemesis was **{}**, who beat you {}.",
+            nemesis,
+            pluralizer::pluralize("time", count as isize, true)
+        ));
+    }
+    if let Some((row, margin)) = luckiest_day(mine, everyone) {
+        highlights.push_str(&format!(
+            "\nYour luckiest day was Wordle #{}, you got it in {} when everyone else needed {:.1} more on average.",
+            row.puzzle.day_offset, row.puzzle.attempts, margin
+        ));
+    }
+
+    vec![overview, highlights]
+}
+
+fn server_recap(year: i32, puzzles: &[PuzzleRow]) -> String {
+    let mut players: HashMap<u64, Vec<&PuzzleRow>> = HashMap::new();
+    for row in puzzles {
+        players.entry(row.submitted_by).or_default().push(row);
+    }
+
+    let mut recap = format!(
+        "**The Server's {} Wordle Wrapped** 🎁\n**{}** played by {}.",
+        year,
+        pluralizer::pluralize("Wordle", puzzles.len() as isize, true),
+        pluralizer::pluralize("player", players.len() as isize, true)
+    );
+
+    if let Some(rows) = players.values().max_by_key(|rows| rows.len()) {
+        recap.push_str(&format!(
+            "\nMost dedicated: **{}** with {}.",
+            rows[0].submitter,
+            pluralizer::pluralize("game", rows.len() as isize, true)
+        ));
+    }
+
+    if let Some((player_id, count)) = daily_wins(puzzles, Ranking::current())
+        .into_iter()
+        .max_by_key(|(_, count)| *count)
+    {
+        recap.push_str(&format!(
+            "\nMost daily wins: **{}** with {}.",
+            players[&player_id][0].submitter,
+            pluralizer::pluralize("win", count as isize, true)
+        ));
+    }
+
+    if let Some((pattern, count)) = most_common_opening(puzzles.iter()) {
+        recap.push_str(&format!(
+            "\nMost common first row: {} ({}).",
+            pattern,
+            pluralizer::pluralize("time", count as isize, true)
+        ));
+    }
+
+    let mut days: BTreeMap<u32, Vec<u32>> = BTreeMap::new();
+    for row in puzzles {
+        days.entry(row.puzzle.day_offset)
+            .or_default()
+            .push(row.puzzle.score());
+    }
+    if let Some((day_offset, average)) = days
+        .iter()
+        .filter(|(_, scores)| scores.len() > 1)
+        .map(|(day, scores)| (day, scores.iter().sum::<u32>() as f64 / scores.len() as f64))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+    {
+        recap.push_str(&format!(
+            "\nHardest day: Wordle #{} at {:.2} attempts on average.",
+            day_offset, average
+        ));
+    }
+
+    recap
+}
+
+/// How many times each player had the best result of the day, ties included.
+pub fn daily_wins(puzzles: &[PuzzleRow], ranking: Ranking) -> HashMap<u64, usize> {
+    let mut best = HashMap::new();
+    for row in puzzles {
+        let key = ranking.puzzle_key(&row.puzzle);
+        best.entry(row.puzzle.day_offset)
+            .and_modify(|best: &mut (u32, bool)| *best = (*best).min(key))
+            .or_insert(key);
+    }
+
+    let mut wins: HashMap<u64, usize> = HashMap::new();
+    for row in puzzles.iter().filter(|row| row.puzzle.solved) {
+        if best.get(&row.puzzle.day_offset) == Some(&ranking.puzzle_key(&row.puzzle)) {
+            *wins.entry(row.submitted_by).or_default() += 1;
+        }
+    }
+
+    wins
+}
+
+/// The most days in a row played.
+pub fn longest_streak(rows: &[&PuzzleRow]) -> usize {
+    let mut days = rows
+        .iter()
+        .map(|row| row.puzzle.day_offset)
+        .collect::<Vec<_>>();
+    days.sort_unstable();
+    days.dedup();
+
+    let (mut longest, mut current) = (0, 0);
+    for (i, day) in days.iter().enumerate() {
+        current = if i > 0 && days[i - 1] + 1 == *day {
+            current + 1
+        } else {
+            1
+        };
+        longest = longest.max(current);
+    }
+
+    longest
+}
+
+fn most_common_opening<'a>(rows: impl Iterator<Item = &'a PuzzleRow>) -> Option<(String, usize)> {
+    let mut openings: HashMap<String, usize> = HashMap::new();
+    for row in rows {
+        if let Some(first) = row.puzzle.board.board.first() {
+            let pattern = first
+                .iter()
+                .map(|tile| tile.render(Theme::default()))
+                .collect::<String>();
+            *openings.entry(pattern).or_default() += 1;
+        }
+    }
+
+    openings.into_iter().max_by_key(|(_, count)| *count)
+}
+
+/// The player that did better than the user most often on the days they both played.
+fn nemesis(
+    mine: &[&PuzzleRow],
+    everyone: &[PuzzleRow],
+    ranking: Ranking,
+) -> Option<(String, usize)> {
+    let keys = mine
+        .iter()
+        .map(|row| (row.puzzle.day_offset, ranking.puzzle_key(&row.puzzle)))
+        .collect::<HashMap<_, _>>();
+    let user = mine.first()?.submitted_by;
+
+    let mut beaten_by: HashMap<&str, usize> = HashMap::new();
+    for row in everyone.iter().filter(|row| row.submitted_by != user) {
+        if keys
+            .get(&row.puzzle.day_offset)
+            .is_some_and(|mine| ranking.puzzle_key(&row.puzzle) < *mine)
+        {
+            *beaten_by.entry(&row.submitter).or_default() += 1;
+        }
+    }
+
+    beaten_by
+        .into_iter()
+        .max_by_key(|(_, count)| *count)
+        .map(|(name, count)| (name.to_string(), count))
+}
+
+/// The day the user beat everyone else's average by the most.
+fn luckiest_day<'a>(
+    mine: &[&'a PuzzleRow],
+    everyone: &[PuzzleRow],
+) -> Option<(&'a PuzzleRow, f64)> {
+    mine.iter()
+        .filter(|row| row.puzzle.solved)
+        .filter_map(|row| {
+            let others = everyone
+                .iter()
+                .filter(|other| {
+                    other.puzzle.day_offset == row.puzzle.day_offset
+                        && other.submitted_by != row.submitted_by
+                })
+                .map(|row| row.puzzle.score())
+                .collect::<Vec<_>>();
+            if others.is_empty() {
+                return None;
+            }
+
+            let average = others.iter().sum::<u32>() as f64 / others.len() as f64;
+            Some((*row, average - row.puzzle.score() as f64))
+        })
+        .filter(|(_, margin)| *margin > 0.0)
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+fn find_puzzles_by_year(year: i32) -> Result<Vec<PuzzleRow>> {
+    find_puzzles_between(
+        NaiveDate::from_ymd_opt(year, 1, 1).unwrap_or(NaiveDate::MIN),
+        NaiveDate::from_ymd_opt(year, 12, 31).unwrap_or(NaiveDate::MAX),
+    )
+}